@@ -0,0 +1,135 @@
+use crate::{Contract, ContractExt};
+use near_sdk::{env, near, near_bindgen, require, AccountId};
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    /// Can mint new tokens.
+    Minter,
+    /// Can pause and unpause the contract.
+    Pauser,
+    /// Can upgrade the contract code.
+    Upgrader,
+    /// Can configure migration sources and the migration window.
+    MigrationAdmin,
+    /// Can block and unblock accounts.
+    Compliance,
+    /// Transfers from these accounts register unregistered receivers
+    /// with storage paid from the sponsorship pool.
+    Distributor,
+}
+
+impl Role {
+    pub const ALL: [Role; 6] = [
+        Role::Minter,
+        Role::Pauser,
+        Role::Upgrader,
+        Role::MigrationAdmin,
+        Role::Compliance,
+        Role::Distributor,
+    ];
+}
+
+/// Methods that trust calls from this contract itself, because they are callbacks or receive
+/// its own tokens. Actions that this contract executes on itself must never call them.
+const CALLBACK_METHODS: &[&str] = &["migrate", "ft_on_transfer", "ft_resolve_transfer"];
+
+pub(crate) fn is_callback_method(method_name: &str) -> bool {
+    CALLBACK_METHODS.contains(&method_name) || method_name.starts_with("on_")
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Proposes `account_id` as new owner. Ownership is only transferred once it calls `accept_owner`.
+    /// Proposing `None` cancels a pending proposal.
+    pub fn propose_owner(&mut self, account_id: Option<AccountId>) {
+        self.assert_owner();
+        self.proposed_owner = account_id;
+    }
+
+    /// Transfers ownership to the caller, together with all roles of the previous owner.
+    pub fn accept_owner(&mut self) {
+        let account_id = env::predecessor_account_id();
+        require!(
+            self.proposed_owner.as_ref() == Some(&account_id),
+            "Only the proposed owner can accept ownership"
+        );
+        for role in Role::ALL {
+            if let Some(members) = self.roles.get_mut(&role) {
+                if members.remove(&self.owner) {
+                    members.insert(account_id.clone());
+                }
+            }
+        }
+        self.owner = account_id;
+        self.proposed_owner = None;
+    }
+
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_owner();
+        self.roles.entry(role).or_default().insert(account_id);
+    }
+
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_owner();
+        if let Some(members) = self.roles.get_mut(&role) {
+            members.remove(&account_id);
+        }
+    }
+
+    pub fn owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    pub fn proposed_owner(&self) -> Option<AccountId> {
+        self.proposed_owner.clone()
+    }
+
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.internal_has_role(role, &account_id)
+    }
+
+    pub fn role_members(&self, role: Role) -> Vec<AccountId> {
+        self.roles
+            .get(&role)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn roles_of(&self, account_id: AccountId) -> Vec<Role> {
+        Role::ALL
+            .into_iter()
+            .filter(|role| self.internal_has_role(*role, &account_id))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Owner checks also pass for calls from this contract itself, which are used to execute
+    /// approved multisig proposals and the few methods that governance proposals can call.
+    pub(crate) fn assert_owner(&self) {
+        let predecessor_id = env::predecessor_account_id();
+        require!(
+            predecessor_id == self.owner || predecessor_id == env::current_account_id(),
+            "Only account owner can call this method"
+        );
+    }
+
+    /// Role checks also pass for calls from this contract itself, which are used to execute
+    /// approved multisig proposals and the few methods that governance proposals can call.
+    pub(crate) fn assert_role(&self, role: Role) {
+        let predecessor_id = env::predecessor_account_id();
+        require!(
+            predecessor_id == env::current_account_id()
+                || self.internal_has_role(role, &predecessor_id),
+            format!("Missing role {:?}", role)
+        );
+    }
+
+    pub(crate) fn internal_has_role(&self, role: Role, account_id: &AccountId) -> bool {
+        self.roles
+            .get(&role)
+            .is_some_and(|members| members.contains(account_id))
+    }
+}
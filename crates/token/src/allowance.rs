@@ -0,0 +1,98 @@
+use crate::{event::Event, Contract, ContractExt};
+use near_sdk::{
+    assert_one_yocto, env, json_types::U128, near_bindgen, require, AccountId, NearToken,
+};
+
+#[near_bindgen]
+impl Contract {
+    /// Allows `spender_id` to transfer up to `amount` tokens of the caller via `ft_transfer_from`.
+    /// Replaces any previous allowance. The attached deposit needs to cover the storage
+    /// of a new allowance and the rest is refunded. Requires at least 1 yoctoNEAR.
+    #[payable]
+    pub fn ft_approve(&mut self, spender_id: AccountId, amount: U128) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Requires attached deposit of at least 1 yoctoNEAR"
+        );
+        let owner_id = env::predecessor_account_id();
+        require!(owner_id != spender_id, "Can't approve yourself");
+        self.assert_not_blocked(&owner_id);
+        let initial_storage_usage = env::storage_usage();
+
+        self.allowances
+            .insert((owner_id.clone(), spender_id.clone()), amount.0);
+        self.allowances.flush();
+
+        self.internal_settle_storage(&owner_id, initial_storage_usage);
+        Event::Approve {
+            owner_id: &owner_id,
+            spender_id: &spender_id,
+            amount,
+        }
+        .emit();
+    }
+
+    /// Removes the allowance of `spender_id` and refunds its storage to the caller.
+    #[payable]
+    pub fn ft_revoke(&mut self, spender_id: AccountId) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        require!(
+            self.allowances
+                .remove(&(owner_id.clone(), spender_id.clone()))
+                .is_some(),
+            "No allowance"
+        );
+        self.allowances.flush();
+
+        self.internal_settle_storage(&owner_id, initial_storage_usage);
+        Event::Revoke {
+            owner_id: &owner_id,
+            spender_id: &spender_id,
+        }
+        .emit();
+    }
+
+    /// Transfers `amount` tokens of `owner_id` to `receiver_id`, spending the caller's allowance.
+    #[payable]
+    pub fn ft_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let spender_id = env::predecessor_account_id();
+        self.internal_spend_allowance(&owner_id, &spender_id, amount.0);
+        self.internal_transfer(&owner_id, &receiver_id, amount.0, memo);
+    }
+
+    pub fn ft_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> U128 {
+        self.allowances
+            .get(&(owner_id, spender_id))
+            .copied()
+            .unwrap_or_default()
+            .into()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_spend_allowance(
+        &mut self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        amount: u128,
+    ) {
+        self.assert_not_blocked(spender_id);
+        let allowance = self
+            .allowances
+            .get_mut(&(owner_id.clone(), spender_id.clone()))
+            .unwrap_or_else(|| env::panic_str("No allowance"));
+        *allowance = allowance
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Allowance exceeded"));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{Contract, ContractExt, PauseFeature};
+use near_contract_standards::fungible_token::events::FtTransfer;
+use near_sdk::{assert_one_yocto, env, json_types::U128, near_bindgen, require, AccountId, Gas};
+
+/// Upper bound on transfers per batch to stay well within a single receipt's gas.
+pub const MAX_BATCH_SIZE: usize = 200;
+/// Gas that needs to be attached per transfer in a batch.
+const GAS_PER_BATCH_TRANSFER: Gas = Gas::from_tgas(1);
+
+#[near_bindgen]
+impl Contract {
+    /// Transfers tokens from the caller to multiple receivers in a single call.
+    /// All receivers need to be registered.
+    #[payable]
+    pub fn ft_batch_transfer(&mut self, transfers: Vec<(AccountId, U128)>, memo: Option<String>) {
+        assert_one_yocto();
+        self.assert_not_paused(PauseFeature::Transfers);
+        require!(!transfers.is_empty(), "No transfers");
+        require!(
+            transfers.len() <= MAX_BATCH_SIZE,
+            format!("At most {} transfers per batch", MAX_BATCH_SIZE)
+        );
+        require!(
+            env::prepaid_gas() >= GAS_PER_BATCH_TRANSFER.saturating_mul(transfers.len() as u64),
+            "More gas is required"
+        );
+        let sender_id = env::predecessor_account_id();
+        self.assert_not_blocked(&sender_id);
+
+        let mut total: u128 = 0;
+        for (receiver_id, amount) in &transfers {
+            require!(
+                *receiver_id != sender_id,
+                "Sender and receiver should be different"
+            );
+            require!(amount.0 > 0, "The amount should be a positive number");
+            self.assert_not_blocked(receiver_id);
+            total = total
+                .checked_add(amount.0)
+                .unwrap_or_else(|| env::panic_str("Total amount overflow"));
+        }
+
+        self.assert_unlocked(&sender_id, total);
+        self.internal_checkpoint(&sender_id);
+        self.token.internal_withdraw(&sender_id, total);
+        for (receiver_id, amount) in &transfers {
+            self.internal_sponsor_registration(&sender_id, receiver_id);
+            self.internal_checkpoint(receiver_id);
+            self.token.internal_deposit(receiver_id, amount.0);
+        }
+        let events: Vec<_> = transfers
+            .iter()
+            .map(|(receiver_id, amount)| FtTransfer {
+                old_owner_id: &sender_id,
+                new_owner_id: receiver_id,
+                amount: *amount,
+                memo: memo.as_deref(),
+            })
+            .collect();
+        FtTransfer::emit_many(&events);
+    }
+}
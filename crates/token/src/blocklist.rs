@@ -0,0 +1,45 @@
+use crate::{event::Event, Contract, ContractExt, Role};
+use near_sdk::{env, near_bindgen, require, AccountId};
+
+#[near_bindgen]
+impl Contract {
+    /// Prevents `account_id` from sending or receiving tokens and from unregistering.
+    pub fn block_account(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Compliance);
+        require!(
+            self.blocked_accounts.insert(account_id.clone()),
+            "Account is already blocked"
+        );
+        Event::AccountBlocked {
+            account_id: &account_id,
+            blocked_by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn unblock_account(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Compliance);
+        require!(
+            self.blocked_accounts.remove(&account_id),
+            "Account is not blocked"
+        );
+        Event::AccountUnblocked {
+            account_id: &account_id,
+            unblocked_by: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn is_blocked(&self, account_id: AccountId) -> bool {
+        self.blocked_accounts.contains(&account_id)
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_not_blocked(&self, account_id: &AccountId) {
+        require!(
+            !self.blocked_accounts.contains(account_id),
+            format!("Account {} is blocked", account_id)
+        );
+    }
+}
@@ -1,12 +1,14 @@
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, PauseFeature};
 use near_contract_standards::fungible_token::FungibleTokenCore;
-use near_sdk::{json_types::U128, near_bindgen, AccountId, PromiseOrValue};
+use near_sdk::{assert_one_yocto, env, json_types::U128, near_bindgen, AccountId, PromiseOrValue};
 
 #[near_bindgen]
 impl FungibleTokenCore for Contract {
     #[payable]
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
-        self.token.ft_transfer(receiver_id, amount, memo)
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0, memo);
     }
 
     #[payable]
@@ -17,6 +19,16 @@ impl FungibleTokenCore for Contract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
+        self.assert_not_paused(PauseFeature::Transfers);
+        self.assert_not_blocked(&env::predecessor_account_id());
+        self.assert_not_blocked(&receiver_id);
+        self.assert_unlocked(&env::predecessor_account_id(), amount.0);
+        if receiver_id == env::current_account_id() {
+            self.internal_register_self();
+        }
+        self.internal_sponsor_registration(&env::predecessor_account_id(), &receiver_id);
+        self.internal_checkpoint(&env::predecessor_account_id());
+        self.internal_checkpoint(&receiver_id);
         self.token.ft_transfer_call(receiver_id, amount, memo, msg)
     }
 
@@ -28,3 +40,24 @@ impl FungibleTokenCore for Contract {
         self.token.ft_balance_of(account_id)
     }
 }
+
+impl Contract {
+    /// Transfers tokens from `sender_id` with all checks that apply to transfers.
+    pub(crate) fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<String>,
+    ) {
+        self.assert_not_paused(PauseFeature::Transfers);
+        self.assert_not_blocked(sender_id);
+        self.assert_not_blocked(receiver_id);
+        self.assert_unlocked(sender_id, amount);
+        self.internal_sponsor_registration(sender_id, receiver_id);
+        self.internal_checkpoint(sender_id);
+        self.internal_checkpoint(receiver_id);
+        self.token
+            .internal_transfer(sender_id, receiver_id, amount, memo);
+    }
+}
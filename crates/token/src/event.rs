@@ -0,0 +1,143 @@
+use crate::{MultisigAction, PauseFeature, Vote};
+use near_sdk::{
+    json_types::{Base58CryptoHash, U128, U64},
+    near_bindgen, AccountId,
+};
+
+#[near_bindgen(event_json(standard = "jlu"))]
+pub enum Event<'a> {
+    #[event_version("1.0.0")]
+    PauseChanged {
+        feature: PauseFeature,
+        paused: bool,
+        account_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    CodeStaged {
+        code_hash: Base58CryptoHash,
+        eligible_at: U64,
+    },
+    #[event_version("1.0.0")]
+    StagedCodeCancelled { code_hash: Base58CryptoHash },
+    #[event_version("1.0.0")]
+    DeployedCodeRegistered { code_hash: Base58CryptoHash },
+    #[event_version("1.0.0")]
+    StagedCodeDeployed { code_hash: Base58CryptoHash },
+    #[event_version("1.0.0")]
+    UpgradeSucceeded { code_hash: Base58CryptoHash },
+    #[event_version("1.0.0")]
+    UpgradeFailed {
+        code_hash: Base58CryptoHash,
+        rollback_code_hash: Option<Base58CryptoHash>,
+    },
+    #[event_version("1.0.0")]
+    MultisigProposed {
+        proposal_id: U64,
+        proposer_id: &'a AccountId,
+        action: &'a MultisigAction,
+    },
+    #[event_version("1.0.0")]
+    MultisigConfirmed {
+        proposal_id: U64,
+        account_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    MultisigRevoked {
+        proposal_id: U64,
+        account_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    MultisigExecuted {
+        proposal_id: U64,
+        account_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    MultisigExecutionFailed {
+        proposal_id: U64,
+        account_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    AccountBlocked {
+        account_id: &'a AccountId,
+        blocked_by: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    AccountUnblocked {
+        account_id: &'a AccountId,
+        unblocked_by: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    TokensRescued {
+        token_id: &'a AccountId,
+        receiver_id: &'a AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    StorageSponsored { account_id: &'a AccountId },
+    #[event_version("1.0.0")]
+    Approve {
+        owner_id: &'a AccountId,
+        spender_id: &'a AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    Revoke {
+        owner_id: &'a AccountId,
+        spender_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    VestingCreated {
+        schedule_id: U64,
+        beneficiary_id: &'a AccountId,
+        total: U128,
+    },
+    #[event_version("1.0.0")]
+    VestingRevoked { schedule_id: U64, unvested: U128 },
+    #[event_version("1.0.0")]
+    TokensLocked {
+        account_id: &'a AccountId,
+        amount: U128,
+        until: U64,
+    },
+    #[event_version("1.0.0")]
+    StakingRewardsFunded {
+        amount: U128,
+        reward_rate: U128,
+        period_end: U64,
+    },
+    #[event_version("1.0.0")]
+    Staked {
+        account_id: &'a AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    Unstaked {
+        account_id: &'a AccountId,
+        amount: U128,
+        unstaking_until: U64,
+    },
+    #[event_version("1.0.0")]
+    RewardsClaimed {
+        account_id: &'a AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    ProposalCreated {
+        proposal_id: u32,
+        proposer_id: &'a AccountId,
+    },
+    #[event_version("1.0.0")]
+    VoteCast {
+        proposal_id: u32,
+        account_id: &'a AccountId,
+        vote: Vote,
+        weight: U128,
+    },
+    #[event_version("1.0.0")]
+    ProposalExecuted { proposal_id: u32 },
+    #[event_version("1.0.0")]
+    ProposalVetoed {
+        proposal_id: u32,
+        account_id: &'a AccountId,
+    },
+}
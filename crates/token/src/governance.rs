@@ -0,0 +1,414 @@
+use crate::{event::Event, Contract, ContractExt};
+use near_sdk::{
+    env,
+    json_types::{Base64VecU8, U128, U64},
+    near, near_bindgen, require,
+    store::{LookupMap, Vector},
+    AccountId, BlockHeight, Gas, GasWeight, IntoStorageKey, NearToken, Promise,
+};
+
+/// Three days in nanoseconds.
+pub const DEFAULT_VOTING_PERIOD: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+/// Two days in nanoseconds.
+pub const DEFAULT_EXECUTION_DELAY: u64 = 2 * 24 * 60 * 60 * 1_000_000_000;
+pub const DEFAULT_PROPOSAL_THRESHOLD_BPS: u16 = 100;
+const BPS_DENOMINATOR: u128 = 10_000;
+const MAX_DESCRIPTION_LENGTH: usize = 2_000;
+/// Every balance change records the balance at the snapshot blocks of all proposals in voting,
+/// so their number is limited.
+pub const MAX_PROPOSALS_IN_VOTING: usize = 10;
+
+/// Methods of this contract that proposals can call. Calls from this contract itself pass
+/// owner and role checks, so this must never include methods that change ownership or roles,
+/// mint or upgrade. Those stay with the owner and the multisig.
+const GOVERNANCE_SELF_METHODS: &[&str] = &["return_self_held_tokens", "cancel_staged_code"];
+
+/// Action that is executed once a proposal has passed and its execution delay is over.
+/// Proposals can't call other contracts, because this contract holds tokens of migration
+/// sources that back redemptions and other tokens that aren't governed by holders.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone)]
+pub enum GovernanceAction {
+    /// Transfers NEAR from the governance treasury.
+    Transfer {
+        receiver_id: AccountId,
+        amount: NearToken,
+    },
+    /// Calls one of `GOVERNANCE_SELF_METHODS` on this contract without deposit.
+    SelfCall {
+        method_name: String,
+        args: Base64VecU8,
+    },
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct GovernanceProposal {
+    pub proposer_id: AccountId,
+    pub description: String,
+    pub action: Option<GovernanceAction>,
+    /// Block at the end of which balances are used as voting weight.
+    pub snapshot_block: U64,
+    /// Total supply at the end of the snapshot block, which the quorum is based on.
+    pub snapshot_total_supply: U128,
+    /// Timestamp in nanoseconds at which voting ends.
+    pub voting_ends_at: U64,
+    /// Timestamp in nanoseconds from which the proposal can be executed if it passed.
+    pub executable_at: U64,
+    pub yes: U128,
+    pub no: U128,
+    pub abstain: U128,
+    pub executed: bool,
+    pub vetoed: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Passed,
+    Rejected,
+    Executed,
+    Vetoed,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct GovernanceConfig {
+    /// Duration in nanoseconds of voting on a proposal.
+    pub voting_period: U64,
+    /// Duration in nanoseconds after voting before a passed proposal can be executed,
+    /// during which the owner can still veto it.
+    pub execution_delay: U64,
+    /// Share in basis points of the snapshot total supply that has to vote, including abstentions.
+    pub quorum_bps: u16,
+    /// Share in basis points of yes and no votes that has to be yes for a proposal to pass.
+    pub threshold_bps: u16,
+    /// Share in basis points of the snapshot total supply that is required to create a proposal.
+    pub proposal_threshold_bps: u16,
+}
+
+#[near(serializers = [borsh])]
+pub struct Governance {
+    config: GovernanceConfig,
+    proposals: Vector<GovernanceProposal>,
+    votes: LookupMap<(u32, AccountId), Vote>,
+    /// NEAR that pays the transfers of proposals.
+    treasury: NearToken,
+    /// Snapshot block and end of voting of each proposal that may still be in voting.
+    voting_snapshots: Vec<(BlockHeight, u64)>,
+}
+
+impl Governance {
+    pub fn new<P, V>(proposals_prefix: P, votes_prefix: V) -> Self
+    where
+        P: IntoStorageKey,
+        V: IntoStorageKey,
+    {
+        Self {
+            config: GovernanceConfig {
+                voting_period: DEFAULT_VOTING_PERIOD.into(),
+                execution_delay: DEFAULT_EXECUTION_DELAY.into(),
+                quorum_bps: 400,
+                threshold_bps: 5_000,
+                proposal_threshold_bps: DEFAULT_PROPOSAL_THRESHOLD_BPS,
+            },
+            proposals: Vector::new(proposals_prefix),
+            votes: LookupMap::new(votes_prefix),
+            treasury: NearToken::from_yoctonear(0),
+            voting_snapshots: Vec::new(),
+        }
+    }
+
+    /// Snapshot blocks of the proposals that are in voting.
+    pub(crate) fn voting_snapshot_blocks(&self) -> Vec<BlockHeight> {
+        let now = env::block_timestamp();
+        self.voting_snapshots
+            .iter()
+            .filter(|(_, voting_ends_at)| *voting_ends_at > now)
+            .map(|(snapshot_block, _)| *snapshot_block)
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_governance_config(&mut self, config: GovernanceConfig) {
+        self.assert_owner();
+        require!(
+            config.quorum_bps as u128 <= BPS_DENOMINATOR
+                && config.threshold_bps as u128 <= BPS_DENOMINATOR
+                && config.proposal_threshold_bps as u128 <= BPS_DENOMINATOR,
+            "Basis points can't exceed 10000"
+        );
+        self.governance.config = config;
+    }
+
+    /// Adds the attached deposit to the treasury that pays the transfers of proposals.
+    #[payable]
+    pub fn fund_governance_treasury(&mut self) {
+        self.governance.treasury = self
+            .governance
+            .treasury
+            .saturating_add(env::attached_deposit());
+    }
+
+    /// Creates a proposal that is voted on with balances at the end of the previous block,
+    /// where the proposer needs at least the proposal threshold. At most
+    /// `MAX_PROPOSALS_IN_VOTING` proposals can be in voting at the same time.
+    /// The attached deposit needs to cover the storage of the proposal and the rest is refunded.
+    #[payable]
+    pub fn create_proposal(
+        &mut self,
+        description: String,
+        action: Option<GovernanceAction>,
+    ) -> u32 {
+        require!(
+            description.len() <= MAX_DESCRIPTION_LENGTH,
+            "Description is too long"
+        );
+        if let Some(GovernanceAction::SelfCall { method_name, .. }) = &action {
+            require!(
+                GOVERNANCE_SELF_METHODS.contains(&method_name.as_str()),
+                "Method can't be called by governance"
+            );
+        }
+        let proposer_id = env::predecessor_account_id();
+        let snapshot_block = env::block_height() - 1;
+        let snapshot_total_supply = self.ft_total_supply_at(snapshot_block.into()).0;
+        let balance = self.internal_balance_of_at(&proposer_id, snapshot_block);
+        let config = &self.governance.config;
+        require!(
+            balance > 0
+                && balance * BPS_DENOMINATOR
+                    >= snapshot_total_supply * config.proposal_threshold_bps as u128,
+            "Not enough tokens to create a proposal"
+        );
+        let now = env::block_timestamp();
+        let voting_ends_at = now + config.voting_period.0;
+        let executable_at = voting_ends_at + config.execution_delay.0;
+        self.governance
+            .voting_snapshots
+            .retain(|(_, voting_ends_at)| *voting_ends_at > now);
+        require!(
+            self.governance.voting_snapshots.len() < MAX_PROPOSALS_IN_VOTING,
+            "Too many proposals in voting"
+        );
+        let initial_storage_usage = env::storage_usage();
+
+        let proposal_id = self.governance.proposals.len();
+        self.governance.proposals.push(GovernanceProposal {
+            proposer_id: proposer_id.clone(),
+            description,
+            action,
+            snapshot_block: snapshot_block.into(),
+            snapshot_total_supply: snapshot_total_supply.into(),
+            voting_ends_at: voting_ends_at.into(),
+            executable_at: executable_at.into(),
+            yes: U128(0),
+            no: U128(0),
+            abstain: U128(0),
+            executed: false,
+            vetoed: false,
+        });
+        self.governance.proposals.flush();
+        self.governance
+            .voting_snapshots
+            .push((snapshot_block, voting_ends_at));
+
+        self.internal_settle_storage(&proposer_id, initial_storage_usage);
+        Event::ProposalCreated {
+            proposal_id,
+            proposer_id: &proposer_id,
+        }
+        .emit();
+        proposal_id
+    }
+
+    /// Votes with the caller's balance at the proposal's snapshot block, which is recorded before
+    /// the balance first changes during voting. The attached deposit needs to cover the storage
+    /// of the vote and the rest is refunded.
+    #[payable]
+    pub fn vote(&mut self, proposal_id: u32, vote: Vote) {
+        let account_id = env::predecessor_account_id();
+        let proposal = self.internal_proposal(proposal_id);
+        require!(!proposal.vetoed, "Proposal has been vetoed");
+        require!(
+            env::block_timestamp() < proposal.voting_ends_at.0,
+            "Voting has ended"
+        );
+        let weight = self.internal_snapshot_balance(&account_id, proposal.snapshot_block.0);
+        require!(weight > 0, "No voting weight");
+        let initial_storage_usage = env::storage_usage();
+
+        require!(
+            self.governance
+                .votes
+                .insert((proposal_id, account_id.clone()), vote)
+                .is_none(),
+            "Already voted"
+        );
+        self.governance.votes.flush();
+        let proposal = &mut self.governance.proposals[proposal_id];
+        let tally = match vote {
+            Vote::Yes => &mut proposal.yes,
+            Vote::No => &mut proposal.no,
+            Vote::Abstain => &mut proposal.abstain,
+        };
+        tally.0 += weight;
+
+        self.internal_settle_storage(&account_id, initial_storage_usage);
+        Event::VoteCast {
+            proposal_id,
+            account_id: &account_id,
+            vote,
+            weight: weight.into(),
+        }
+        .emit();
+    }
+
+    /// Executes a proposal that passed once its execution delay is over. Anyone can execute it.
+    /// Transfers are paid from the governance treasury.
+    pub fn execute_proposal(&mut self, proposal_id: u32) -> Option<Promise> {
+        require!(
+            self.proposal_status(proposal_id) == ProposalStatus::Passed,
+            "Proposal has not passed"
+        );
+        let proposal = &mut self.governance.proposals[proposal_id];
+        require!(
+            env::block_timestamp() >= proposal.executable_at.0,
+            "Proposal can't be executed yet"
+        );
+        proposal.executed = true;
+        let action = proposal.action.clone();
+
+        Event::ProposalExecuted { proposal_id }.emit();
+        action.map(|action| match action {
+            GovernanceAction::Transfer {
+                receiver_id,
+                amount,
+            } => {
+                self.governance.treasury = self
+                    .governance
+                    .treasury
+                    .checked_sub(amount)
+                    .unwrap_or_else(|| {
+                        env::panic_str("Not enough NEAR in the governance treasury")
+                    });
+                Promise::new(receiver_id).transfer(amount)
+            }
+            GovernanceAction::SelfCall { method_name, args } => {
+                Promise::new(env::current_account_id()).function_call_weight(
+                    method_name,
+                    args.0,
+                    NearToken::from_yoctonear(0),
+                    Gas::from_gas(0),
+                    GasWeight(1),
+                )
+            }
+        })
+    }
+
+    /// Vetoes a proposal that has not been executed yet. Since owner checks pass for calls from
+    /// this contract itself, the multisig can veto proposals as well.
+    pub fn veto_proposal(&mut self, proposal_id: u32) {
+        self.assert_owner();
+        let proposal = self
+            .governance
+            .proposals
+            .get_mut(proposal_id)
+            .unwrap_or_else(|| env::panic_str("Unknown proposal"));
+        require!(!proposal.executed, "Proposal has been executed");
+        proposal.vetoed = true;
+        let voting_snapshot = (proposal.snapshot_block.0, proposal.voting_ends_at.0);
+        if let Some(index) = self
+            .governance
+            .voting_snapshots
+            .iter()
+            .position(|snapshot| *snapshot == voting_snapshot)
+        {
+            self.governance.voting_snapshots.swap_remove(index);
+        }
+
+        Event::ProposalVetoed {
+            proposal_id,
+            account_id: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn governance_config(&self) -> GovernanceConfig {
+        self.governance.config.clone()
+    }
+
+    pub fn governance_treasury(&self) -> NearToken {
+        self.governance.treasury
+    }
+
+    pub fn proposal(&self, proposal_id: u32) -> Option<GovernanceProposal> {
+        self.governance.proposals.get(proposal_id).cloned()
+    }
+
+    pub fn proposal_status(&self, proposal_id: u32) -> ProposalStatus {
+        let proposal = self.internal_proposal(proposal_id);
+        if proposal.executed {
+            return ProposalStatus::Executed;
+        }
+        if proposal.vetoed {
+            return ProposalStatus::Vetoed;
+        }
+        if env::block_timestamp() < proposal.voting_ends_at.0 {
+            return ProposalStatus::Active;
+        }
+        let config = &self.governance.config;
+        let total_supply = proposal.snapshot_total_supply.0;
+        let votes = proposal.yes.0 + proposal.no.0 + proposal.abstain.0;
+        let quorum_reached = votes * BPS_DENOMINATOR >= total_supply * config.quorum_bps as u128;
+        let threshold_reached = proposal.yes.0 * BPS_DENOMINATOR
+            > (proposal.yes.0 + proposal.no.0) * config.threshold_bps as u128;
+        if quorum_reached && threshold_reached {
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Rejected
+        }
+    }
+
+    pub fn proposals(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<GovernanceProposal> {
+        self.governance
+            .proposals
+            .iter()
+            .skip(from_index.unwrap_or_default() as usize)
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .cloned()
+            .collect()
+    }
+
+    pub fn vote_of(&self, proposal_id: u32, account_id: AccountId) -> Option<Vote> {
+        self.governance
+            .votes
+            .get(&(proposal_id, account_id))
+            .copied()
+    }
+}
+
+impl Contract {
+    fn internal_proposal(&self, proposal_id: u32) -> &GovernanceProposal {
+        self.governance
+            .proposals
+            .get(proposal_id)
+            .unwrap_or_else(|| env::panic_str("Unknown proposal"))
+    }
+}
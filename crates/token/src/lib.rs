@@ -1,8 +1,43 @@
+mod access;
+mod allowance;
+mod batch;
+mod blocklist;
 mod core;
+mod event;
+mod governance;
+mod lock;
+mod migration;
+mod multisig;
+mod pause;
+mod permit;
+mod relay;
+mod rescue;
+mod snapshot;
+mod sponsor;
+mod staking;
+mod state;
 mod storage;
+mod supply;
+mod upgrade;
+mod vesting;
+
+pub use access::*;
+pub use governance::*;
+pub use lock::*;
+pub use migration::*;
+pub use multisig::*;
+pub use pause::*;
+pub use permit::*;
+pub use relay::*;
+pub use snapshot::*;
+pub use sponsor::*;
+pub use staking::*;
+pub use state::*;
+pub use upgrade::*;
+pub use vesting::*;
 
 use near_contract_standards::fungible_token::{
-    events::{FtBurn, FtMint},
+    events::FtBurn,
     metadata::{FungibleTokenMetadata, FungibleTokenMetadataProvider},
     receiver::FungibleTokenReceiver,
     FungibleToken, FungibleTokenResolver,
@@ -11,13 +46,39 @@ use near_sdk::{
     borsh::{BorshDeserialize, BorshSerialize},
     env,
     json_types::U128,
-    near_bindgen, require, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue,
+    near_bindgen,
+    store::{IterableMap, LookupMap, LookupSet},
+    AccountId, BorshStorageKey, PanicOnDefault, PromiseOrValue, PublicKey,
 };
+use std::collections::BTreeSet;
 
 #[derive(BorshStorageKey, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
 pub enum StorageKey {
     Token,
+    MigrationSources,
+    MigratedAccounts,
+    Roles,
+    Version,
+    StagedCode,
+    DeployedCode,
+    RollbackCode,
+    MultisigProposals,
+    BlockedAccounts,
+    SponsoredAccounts,
+    Allowances,
+    SigningKeys,
+    Nonces,
+    VestingSchedules,
+    Locks,
+    Stakers,
+    CheckpointLengths,
+    CheckpointEntries,
+    TotalSupplyCheckpoints,
+    GovernanceProposals,
+    GovernanceVotes,
+    VestingScheduleIds,
+    SnapshotBalances,
 }
 
 #[near_bindgen(contract_metadata(
@@ -29,7 +90,49 @@ pub enum StorageKey {
 #[borsh(crate = "near_sdk::borsh")]
 pub struct Contract {
     owner: AccountId,
-    migrate_address: AccountId,
+    /// Account that has been proposed as new owner and still needs to accept.
+    proposed_owner: Option<AccountId>,
+    roles: LookupMap<Role, BTreeSet<AccountId>>,
+    /// Tokens that can be migrated into this token, keyed by their contract ID.
+    migration_sources: IterableMap<AccountId, MigrationSource>,
+    /// Timestamp in nanoseconds from which tokens are accepted for migration.
+    migration_start: u64,
+    /// Timestamp in nanoseconds at which migration closes, if any.
+    migration_end: Option<u64>,
+    /// Total amount of new tokens minted through migration.
+    migration_minted: u128,
+    /// Maximum amount of new tokens that can be minted through migration.
+    migration_cap: Option<u128>,
+    /// Amount of new tokens minted through migration per account.
+    migrated_accounts: LookupMap<AccountId, u128>,
+    /// Maximum total supply, if any.
+    max_supply: Option<u128>,
+    paused: PauseStatus,
+    /// Code that has been staged for deployment. The code itself is stored under `StorageKey::StagedCode`.
+    staged_code: Option<StagedCode>,
+    /// Duration in nanoseconds that staged code has to wait before it can be deployed.
+    upgrade_timelock: u64,
+    multisig: Multisig,
+    /// Accounts that are not allowed to move tokens.
+    blocked_accounts: LookupSet<AccountId>,
+    sponsorship: Sponsorship,
+    /// Amount each spender is allowed to transfer on behalf of an owner, keyed by `(owner, spender)`.
+    allowances: LookupMap<(AccountId, AccountId), u128>,
+    /// Registered keys for signed messages of accounts that are not implicit accounts.
+    signing_keys: LookupMap<AccountId, PublicKey>,
+    /// Next nonce of each account's signed messages.
+    nonces: LookupMap<AccountId, u64>,
+    vesting: Vesting,
+    /// Voluntary locks of each account, including expired ones until they are pruned.
+    locks: LookupMap<AccountId, Vec<TokenLock>>,
+    staking: Staking,
+    checkpoints: Checkpoints,
+    governance: Governance,
+    /// Burned amount of redemptions whose transfer of source tokens hasn't resolved yet.
+    /// It is minted again if the transfer fails, so it stays reserved against max supply.
+    redeem_pending: u128,
+    /// IDs of the vesting schedules of each beneficiary.
+    vesting_schedule_ids: LookupMap<AccountId, Vec<u64>>,
     token: FungibleToken,
 }
 
@@ -37,29 +140,68 @@ pub struct Contract {
 impl Contract {
     #[init]
     pub fn new(owner: AccountId, migrate_address: AccountId) -> Self {
-        Self {
+        Self::write_state_version();
+        Self::init(
             owner,
             migrate_address,
-            token: FungibleToken::new(StorageKey::Token),
-        }
+            FungibleToken::new(StorageKey::Token),
+        )
     }
 
-    pub fn migrate(&mut self) {
-        // empty for now
+    /// Migrates any previous state layout to the current one.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let contract = VersionedContract::read().into_current();
+        Self::write_state_version();
+        contract
     }
+}
 
-    pub fn upgrade(&self) -> Promise {
-        require!(
-            env::predecessor_account_id() == self.owner,
-            "Only account owner can update the code"
-        );
-
-        let code = env::input().expect("Error: No input").to_vec();
-
-        Promise::new(env::current_account_id())
-            .deploy_contract(code)
-            .then(Self::ext(env::current_account_id()).migrate())
-            .as_return()
+impl Contract {
+    fn init(owner: AccountId, migrate_address: AccountId, token: FungibleToken) -> Self {
+        let mut roles = LookupMap::new(StorageKey::Roles);
+        for role in Role::ALL {
+            roles.insert(role, BTreeSet::from([owner.clone()]));
+        }
+        let migration_sources = Self::new_migration_sources(migrate_address);
+        Self {
+            owner,
+            proposed_owner: None,
+            roles,
+            migration_sources,
+            migration_start: env::block_timestamp(),
+            migration_end: None,
+            migration_minted: 0,
+            migration_cap: None,
+            migrated_accounts: LookupMap::new(StorageKey::MigratedAccounts),
+            max_supply: None,
+            paused: PauseStatus::default(),
+            staged_code: None,
+            upgrade_timelock: DEFAULT_UPGRADE_TIMELOCK,
+            multisig: Multisig::new(StorageKey::MultisigProposals),
+            blocked_accounts: LookupSet::new(StorageKey::BlockedAccounts),
+            sponsorship: Sponsorship::new(StorageKey::SponsoredAccounts),
+            allowances: LookupMap::new(StorageKey::Allowances),
+            signing_keys: LookupMap::new(StorageKey::SigningKeys),
+            nonces: LookupMap::new(StorageKey::Nonces),
+            vesting: Vesting::new(StorageKey::VestingSchedules),
+            locks: LookupMap::new(StorageKey::Locks),
+            staking: Staking::new(StorageKey::Stakers),
+            checkpoints: Checkpoints::new(
+                StorageKey::CheckpointLengths,
+                StorageKey::CheckpointEntries,
+                StorageKey::TotalSupplyCheckpoints,
+                StorageKey::SnapshotBalances,
+            ),
+            governance: Governance::new(
+                StorageKey::GovernanceProposals,
+                StorageKey::GovernanceVotes,
+            ),
+            redeem_pending: 0,
+            vesting_schedule_ids: LookupMap::new(StorageKey::VestingScheduleIds),
+            token,
+        }
     }
 }
 
@@ -72,6 +214,9 @@ impl FungibleTokenResolver for Contract {
         receiver_id: AccountId,
         amount: U128,
     ) -> U128 {
+        self.internal_checkpoint(&sender_id);
+        self.internal_checkpoint(&receiver_id);
+        self.internal_checkpoint_supply();
         let (used_amount, burned_amount) =
             self.token
                 .internal_ft_resolve_transfer(&sender_id, receiver_id, amount);
@@ -93,20 +238,19 @@ impl FungibleTokenReceiver for Contract {
         &mut self,
         sender_id: AccountId,
         amount: U128,
-        #[allow(unused_variables)] msg: String,
+        msg: String,
     ) -> PromiseOrValue<U128> {
-        if env::predecessor_account_id() == self.migrate_address {
-            if !self.token.accounts.contains_key(&sender_id) {
-                self.token.internal_register_account(&sender_id);
-            }
-            self.token.internal_deposit(&sender_id, amount.into());
-            FtMint {
-                owner_id: &sender_id,
-                amount,
-                memo: None,
-            }
-            .emit();
-            PromiseOrValue::Value(0.into())
+        let token_id = env::predecessor_account_id();
+        if self.migration_sources.contains_key(&token_id) {
+            PromiseOrValue::Value(
+                self.internal_migrate(&token_id, &sender_id, amount.0)
+                    .into(),
+            )
+        } else if token_id == env::current_account_id() {
+            PromiseOrValue::Value(
+                self.internal_on_self_transfer(&sender_id, amount.0, &msg)
+                    .into(),
+            )
         } else {
             PromiseOrValue::Value(amount)
         }
@@ -0,0 +1,109 @@
+use crate::{event::Event, Contract, ContractExt};
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    near, near_bindgen, require, AccountId,
+};
+
+/// Maximum number of active locks per account.
+pub const MAX_LOCKS_PER_ACCOUNT: usize = 20;
+
+/// Part of an account's balance that can't be spent before `until`.
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct TokenLock {
+    pub amount: U128,
+    /// Timestamp in nanoseconds at which the tokens become spendable again.
+    pub until: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Locks `amount` of the caller's unlocked tokens until the timestamp `until` in nanoseconds.
+    /// Locked tokens still count toward `ft_balance_of`. The attached deposit needs to cover
+    /// the storage of the lock and the rest is refunded.
+    #[payable]
+    pub fn lock(&mut self, amount: U128, until: U64) {
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(
+            until.0 > env::block_timestamp(),
+            "Lock has to end in the future"
+        );
+        let account_id = env::predecessor_account_id();
+        self.assert_unlocked(&account_id, amount.0);
+        let initial_storage_usage = env::storage_usage();
+
+        let now = env::block_timestamp();
+        let locks = self.locks.entry(account_id.clone()).or_default();
+        locks.retain(|lock| lock.until.0 > now);
+        require!(
+            locks.len() < MAX_LOCKS_PER_ACCOUNT,
+            format!("At most {} locks per account", MAX_LOCKS_PER_ACCOUNT)
+        );
+        locks.push(TokenLock { amount, until });
+        self.locks.flush();
+
+        self.internal_settle_storage(&account_id, initial_storage_usage);
+        Event::TokensLocked {
+            account_id: &account_id,
+            amount,
+            until,
+        }
+        .emit();
+    }
+
+    /// Active locks of `account_id`.
+    pub fn locks_of(&self, account_id: AccountId) -> Vec<TokenLock> {
+        let now = env::block_timestamp();
+        self.locks
+            .get(&account_id)
+            .map(|locks| {
+                locks
+                    .iter()
+                    .filter(|lock| lock.until.0 > now)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn ft_locked_balance_of(&self, account_id: AccountId) -> U128 {
+        self.internal_locked_balance(&account_id).into()
+    }
+
+    pub fn ft_unlocked_balance_of(&self, account_id: AccountId) -> U128 {
+        self.internal_unlocked_balance(&account_id).into()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_locked_balance(&self, account_id: &AccountId) -> u128 {
+        let now = env::block_timestamp();
+        self.locks
+            .get(account_id)
+            .map(|locks| {
+                locks
+                    .iter()
+                    .filter(|lock| lock.until.0 > now)
+                    .map(|lock| lock.amount.0)
+                    .sum()
+            })
+            .unwrap_or_default()
+    }
+
+    fn internal_unlocked_balance(&self, account_id: &AccountId) -> u128 {
+        self.token
+            .accounts
+            .get(account_id)
+            .unwrap_or_default()
+            .saturating_sub(self.internal_locked_balance(account_id))
+    }
+
+    /// Panics if `account_id` can't spend `amount` without touching locked tokens.
+    pub(crate) fn assert_unlocked(&self, account_id: &AccountId, amount: u128) {
+        require!(
+            amount <= self.internal_unlocked_balance(account_id),
+            "Not enough unlocked balance"
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use crate::{Contract, ContractExt, PauseFeature, Role, StorageKey};
+use near_contract_standards::{
+    fungible_token::{core::ext_ft_core, events::FtBurn},
+    storage_management::ext_storage_management,
+};
+use near_sdk::{
+    assert_one_yocto, env,
+    json_types::{U128, U64},
+    near, near_bindgen, require,
+    store::IterableMap,
+    AccountId, Gas, NearToken, Promise, PromiseResult,
+};
+use std::collections::BTreeMap;
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_STORAGE_UNREGISTER: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_SWEEP: Gas = Gas::from_tgas(5);
+const GAS_FOR_RESOLVE_REDEEM: Gas = Gas::from_tgas(10);
+
+/// A token that is accepted for migration into this token.
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct MigrationSource {
+    pub ratio: MigrationRatio,
+    /// Maximum amount of source tokens that can be migrated.
+    pub cap: Option<U128>,
+    pub enabled: bool,
+    /// Total amount of source tokens that have been migrated, net of redemptions.
+    pub migrated: U128,
+    /// Total amount of new tokens that have been minted for this source, net of redemptions.
+    pub minted: U128,
+    /// Amount of source tokens received through migration that are still held by this contract.
+    pub balance: U128,
+}
+
+impl MigrationSource {
+    pub fn new(ratio: MigrationRatio, cap: Option<U128>) -> Self {
+        Self {
+            ratio,
+            cap,
+            enabled: true,
+            migrated: 0.into(),
+            minted: 0.into(),
+            balance: 0.into(),
+        }
+    }
+
+    /// Amount of source tokens that can still be migrated before reaching the cap.
+    pub fn remaining(&self) -> u128 {
+        self.cap
+            .map_or(u128::MAX, |cap| cap.0.saturating_sub(self.migrated.0))
+    }
+}
+
+#[near(serializers = [json])]
+pub struct MigrationStats {
+    /// Total amount of new tokens that have been minted through migration.
+    pub total_minted: U128,
+    /// Maximum amount of new tokens that can be minted through migration.
+    pub total_cap: Option<U128>,
+    pub sources: BTreeMap<AccountId, MigrationSourceStats>,
+}
+
+#[near(serializers = [json])]
+pub struct MigrationSourceStats {
+    pub migrated: U128,
+    pub minted: U128,
+    pub balance: U128,
+    pub cap: Option<U128>,
+}
+
+#[near(serializers = [json])]
+pub struct MigrationWindow {
+    pub start: U64,
+    pub end: Option<U64>,
+}
+
+/// Exchange ratio of new tokens per old token, always stored in lowest terms.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy)]
+pub struct MigrationRatio {
+    pub numerator: U128,
+    pub denominator: U128,
+}
+
+impl MigrationRatio {
+    pub fn new(numerator: u128, denominator: u128) -> Self {
+        require!(
+            numerator > 0 && denominator > 0,
+            "Migration ratio must be positive"
+        );
+        let divisor = gcd(numerator, denominator);
+        Self {
+            numerator: (numerator / divisor).into(),
+            denominator: (denominator / divisor).into(),
+        }
+    }
+
+    /// Converts old tokens into new tokens and returns `(used_amount, minted_amount)`.
+    ///
+    /// Only whole multiples of the denominator are converted, so the conversion is exact
+    /// and never rounds in anyone's favor. The remaining dust is not used and gets refunded.
+    pub fn convert(&self, amount: u128) -> (u128, u128) {
+        let units = amount / self.denominator.0;
+        let minted_amount = units
+            .checked_mul(self.numerator.0)
+            .unwrap_or_else(|| env::panic_str("Migration amount overflow"));
+        (units * self.denominator.0, minted_amount)
+    }
+
+    /// Largest amount of source tokens that converts to at most `minted_amount` new tokens.
+    pub fn max_input(&self, minted_amount: u128) -> u128 {
+        (minted_amount / self.numerator.0).saturating_mul(self.denominator.0)
+    }
+}
+
+impl std::fmt::Display for MigrationRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.numerator.0, self.denominator.0)
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the time window in nanoseconds during which tokens can be migrated.
+    /// Without an end, migration stays open until `close_migration` is called.
+    pub fn set_migration_window(&mut self, start: U64, end: Option<U64>) {
+        self.assert_role(Role::MigrationAdmin);
+        if let Some(end) = end {
+            require!(
+                end.0 > start.0,
+                "Migration end must be after migration start"
+            );
+        }
+        self.migration_start = start.0;
+        self.migration_end = end.map(|end| end.0);
+    }
+
+    /// Closes migration immediately. Tokens sent afterwards are refunded.
+    pub fn close_migration(&mut self) {
+        self.assert_role(Role::MigrationAdmin);
+        let now = env::block_timestamp();
+        if self.migration_end.map_or(true, |end| end > now) {
+            self.migration_end = Some(now);
+        }
+    }
+
+    /// Accepts `token_id` for migration, minting `numerator` new tokens per `denominator` source tokens.
+    pub fn add_migration_source(
+        &mut self,
+        token_id: AccountId,
+        numerator: U128,
+        denominator: U128,
+        cap: Option<U128>,
+    ) {
+        self.assert_role(Role::MigrationAdmin);
+        require!(
+            !self.migration_sources.contains_key(&token_id),
+            "Migration source already exists"
+        );
+        let ratio = MigrationRatio::new(numerator.0, denominator.0);
+        self.migration_sources
+            .insert(token_id, MigrationSource::new(ratio, cap));
+    }
+
+    /// Removes a migration source. All of its tokens must have been swept before.
+    pub fn remove_migration_source(&mut self, token_id: AccountId) {
+        self.assert_role(Role::MigrationAdmin);
+        let source = self.internal_migration_source(&token_id);
+        require!(
+            source.balance.0 == 0,
+            "Migration source still has a balance"
+        );
+        self.migration_sources.remove(&token_id);
+    }
+
+    pub fn set_migration_source_enabled(&mut self, token_id: AccountId, enabled: bool) {
+        self.assert_role(Role::MigrationAdmin);
+        self.internal_migration_source_mut(&token_id).enabled = enabled;
+    }
+
+    /// Sets how many new tokens are minted per source token, e.g. `1000:1` for a redenomination
+    /// or `1:1000` if the source token has 3 more decimals than this token.
+    pub fn set_migration_ratio(&mut self, token_id: AccountId, numerator: U128, denominator: U128) {
+        self.assert_role(Role::MigrationAdmin);
+        self.internal_migration_source_mut(&token_id).ratio =
+            MigrationRatio::new(numerator.0, denominator.0);
+    }
+
+    pub fn set_migration_cap(&mut self, token_id: AccountId, cap: Option<U128>) {
+        self.assert_role(Role::MigrationAdmin);
+        self.internal_migration_source_mut(&token_id).cap = cap;
+    }
+
+    /// Sets the maximum amount of new tokens that can be minted through migration across all sources.
+    pub fn set_total_migration_cap(&mut self, cap: Option<U128>) {
+        self.assert_role(Role::MigrationAdmin);
+        self.migration_cap = cap.map(|cap| cap.0);
+    }
+
+    /// Sends all `token_id` tokens collected through migration to `receiver_id`,
+    /// or burns them by force unregistering from the source token contract if no receiver is given.
+    /// Can only be called after migration has ended.
+    pub fn sweep_migrated_tokens(
+        &mut self,
+        token_id: AccountId,
+        receiver_id: Option<AccountId>,
+    ) -> Promise {
+        self.assert_role(Role::MigrationAdmin);
+        require!(
+            self.migration_end
+                .is_some_and(|end| end <= env::block_timestamp()),
+            "Migration has not ended yet"
+        );
+        let source = self.internal_migration_source_mut(&token_id);
+        let amount = source.balance;
+        require!(amount.0 > 0, "Nothing to sweep");
+        source.balance = 0.into();
+
+        let sweep = if let Some(receiver_id) = receiver_id {
+            ext_ft_core::ext(token_id.clone())
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(receiver_id, amount, Some("JLU migration sweep".to_string()))
+        } else {
+            ext_storage_management::ext(token_id.clone())
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(GAS_FOR_STORAGE_UNREGISTER)
+                .storage_unregister(Some(true))
+        };
+        sweep.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_SWEEP)
+                .on_sweep_migrated_tokens(token_id, amount),
+        )
+    }
+
+    #[private]
+    pub fn on_sweep_migrated_tokens(&mut self, token_id: AccountId, amount: U128) -> bool {
+        let swept = match env::promise_result(0) {
+            // `storage_unregister` returns `false` if nothing has been unregistered
+            PromiseResult::Successful(value) => value != b"false",
+            PromiseResult::Failed => false,
+        };
+        if !swept {
+            if let Some(source) = self.migration_sources.get_mut(&token_id) {
+                source.balance.0 += amount.0;
+            }
+        }
+        swept
+    }
+
+    /// Burns `amount` new tokens of the caller and sends back the equivalent amount of `token_id` tokens.
+    /// Only whole multiples of the migration ratio's numerator are redeemed.
+    /// If the transfer of source tokens fails, the burned tokens are minted again.
+    #[payable]
+    pub fn redeem(&mut self, token_id: AccountId, amount: U128) -> Promise {
+        assert_one_yocto();
+        self.assert_not_paused(PauseFeature::Migration);
+        require!(self.is_migration_open(), "Migration is not open");
+        let account_id = env::predecessor_account_id();
+        self.assert_not_blocked(&account_id);
+        let source = self.internal_migration_source(&token_id);
+        require!(source.enabled, "Migration source is disabled");
+        let units = amount.0 / source.ratio.numerator.0;
+        let burned_amount = units * source.ratio.numerator.0;
+        let redeemed_amount = units * source.ratio.denominator.0;
+        require!(redeemed_amount > 0, "Amount too small to redeem");
+        require!(
+            redeemed_amount <= source.balance.0,
+            "Not enough source tokens to redeem"
+        );
+
+        self.assert_unlocked(&account_id, burned_amount);
+        self.internal_unmigrate(&token_id, &account_id, redeemed_amount, burned_amount);
+        self.redeem_pending += burned_amount;
+        self.internal_checkpoint(&account_id);
+        self.internal_checkpoint_supply();
+        self.token.internal_withdraw(&account_id, burned_amount);
+        FtBurn {
+            owner_id: &account_id,
+            amount: burned_amount.into(),
+            memo: Some("redeem"),
+        }
+        .emit();
+
+        ext_ft_core::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(
+                account_id.clone(),
+                redeemed_amount.into(),
+                Some("JLU redeem".to_string()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_REDEEM)
+                    .on_redeem(
+                        token_id,
+                        account_id,
+                        redeemed_amount.into(),
+                        burned_amount.into(),
+                    ),
+            )
+    }
+
+    /// Mints the burned tokens again, if the transfer of source tokens failed.
+    /// Returns the amount of source tokens that have been redeemed.
+    ///
+    /// The burned amount stays reserved against max supply and the total migration cap
+    /// until this resolves, so that the refund never exceeds either of them.
+    #[private]
+    pub fn on_redeem(
+        &mut self,
+        token_id: AccountId,
+        account_id: AccountId,
+        redeemed_amount: U128,
+        burned_amount: U128,
+    ) -> U128 {
+        self.redeem_pending -= burned_amount.0;
+        if near_sdk::is_promise_success() {
+            return redeemed_amount;
+        }
+        if let Some(source) = self.migration_sources.get_mut(&token_id) {
+            source.migrated.0 += redeemed_amount.0;
+            source.minted.0 += burned_amount.0;
+            source.balance.0 += redeemed_amount.0;
+        }
+        self.migration_minted += burned_amount.0;
+        *self
+            .migrated_accounts
+            .entry(account_id.clone())
+            .or_default() += burned_amount.0;
+
+        self.internal_mint(&account_id, burned_amount.0, Some("redeem refund"));
+        0.into()
+    }
+
+    pub fn migration_sources(&self) -> BTreeMap<AccountId, MigrationSource> {
+        self.migration_sources
+            .iter()
+            .map(|(token_id, source)| (token_id.clone(), source.clone()))
+            .collect()
+    }
+
+    pub fn migration_source(&self, token_id: AccountId) -> Option<MigrationSource> {
+        self.migration_sources.get(&token_id).cloned()
+    }
+
+    pub fn migration_stats(&self) -> MigrationStats {
+        MigrationStats {
+            total_minted: self.migration_minted.into(),
+            total_cap: self.migration_cap.map(Into::into),
+            sources: self
+                .migration_sources
+                .iter()
+                .map(|(token_id, source)| {
+                    (
+                        token_id.clone(),
+                        MigrationSourceStats {
+                            migrated: source.migrated,
+                            minted: source.minted,
+                            balance: source.balance,
+                            cap: source.cap,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Amount of new tokens that have been minted for `account_id` through migration.
+    pub fn migrated_amount_of(&self, account_id: AccountId) -> U128 {
+        self.migrated_accounts
+            .get(&account_id)
+            .copied()
+            .unwrap_or_default()
+            .into()
+    }
+
+    pub fn migration_ratio(&self, token_id: AccountId) -> MigrationRatio {
+        self.internal_migration_source(&token_id).ratio
+    }
+
+    pub fn migration_window(&self) -> MigrationWindow {
+        MigrationWindow {
+            start: self.migration_start.into(),
+            end: self.migration_end.map(Into::into),
+        }
+    }
+
+    pub fn is_migration_open(&self) -> bool {
+        let now = env::block_timestamp();
+        now >= self.migration_start && self.migration_end.map_or(true, |end| now < end)
+    }
+}
+
+impl Contract {
+    pub(crate) fn new_migration_sources(
+        migrate_address: AccountId,
+    ) -> IterableMap<AccountId, MigrationSource> {
+        let mut migration_sources = IterableMap::new(StorageKey::MigrationSources);
+        migration_sources.insert(
+            migrate_address,
+            MigrationSource::new(MigrationRatio::new(1, 1), None),
+        );
+        migration_sources
+    }
+
+    fn internal_migration_source(&self, token_id: &AccountId) -> &MigrationSource {
+        self.migration_sources
+            .get(token_id)
+            .unwrap_or_else(|| env::panic_str("Unknown migration source"))
+    }
+
+    fn internal_migration_source_mut(&mut self, token_id: &AccountId) -> &mut MigrationSource {
+        self.migration_sources
+            .get_mut(token_id)
+            .unwrap_or_else(|| env::panic_str("Unknown migration source"))
+    }
+
+    /// Mints new tokens for `token_id` tokens received through migration.
+    /// Returns the amount of source tokens that have not been used and need to be refunded.
+    pub(crate) fn internal_migrate(
+        &mut self,
+        token_id: &AccountId,
+        sender_id: &AccountId,
+        amount: u128,
+    ) -> u128 {
+        if !self.is_migration_open() || self.paused.get(PauseFeature::Migration) {
+            return amount;
+        }
+        let total_remaining = self
+            .migration_cap
+            .map_or(u128::MAX, |cap| {
+                cap.saturating_sub(self.migration_minted + self.redeem_pending)
+            })
+            .min(self.mintable_amount());
+        let source = self.internal_migration_source_mut(token_id);
+        if !source.enabled {
+            return amount;
+        }
+        let max_amount = source
+            .remaining()
+            .min(source.ratio.max_input(total_remaining));
+        let (used_amount, minted_amount) = source.ratio.convert(amount.min(max_amount));
+        if minted_amount == 0 {
+            return amount;
+        }
+        source.migrated.0 += used_amount;
+        source.minted.0 += minted_amount;
+        source.balance.0 += used_amount;
+        let memo = format!("migration ratio {}", source.ratio);
+
+        self.migration_minted += minted_amount;
+        let migrated_amount = self.migrated_accounts.entry(sender_id.clone()).or_default();
+        *migrated_amount += minted_amount;
+
+        self.internal_mint(sender_id, minted_amount, Some(&memo));
+        amount - used_amount
+    }
+
+    /// Reverts the accounting of a migration for redeemed tokens.
+    fn internal_unmigrate(
+        &mut self,
+        token_id: &AccountId,
+        account_id: &AccountId,
+        redeemed_amount: u128,
+        burned_amount: u128,
+    ) {
+        let source = self.internal_migration_source_mut(token_id);
+        source.migrated.0 = source.migrated.0.saturating_sub(redeemed_amount);
+        source.minted.0 = source.minted.0.saturating_sub(burned_amount);
+        source.balance.0 -= redeemed_amount;
+        self.migration_minted = self.migration_minted.saturating_sub(burned_amount);
+        if let Some(migrated_amount) = self.migrated_accounts.get_mut(account_id) {
+            *migrated_amount = migrated_amount.saturating_sub(burned_amount);
+        }
+    }
+}
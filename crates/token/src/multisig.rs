@@ -0,0 +1,431 @@
+use crate::{access::is_callback_method, event::Event, Contract, ContractExt, Role};
+use near_sdk::{
+    env,
+    json_types::{Base58CryptoHash, Base64VecU8, U128, U64},
+    near, near_bindgen, require,
+    serde_json::json,
+    store::IterableMap,
+    AccountId, Gas, GasWeight, IntoStorageKey, NearToken, Promise,
+};
+use std::collections::BTreeSet;
+
+const GAS_FOR_RESOLVE_EXECUTION: Gas = Gas::from_tgas(10);
+
+/// Seven days in nanoseconds.
+pub const DEFAULT_PROPOSAL_DURATION: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+/// A privileged action that is executed by this contract calling itself
+/// once enough multisig members have confirmed it.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone)]
+pub enum MultisigAction {
+    /// Deploys the staged code, if it matches `code_hash`. The upgrade timelock still applies.
+    DeployStaged {
+        code_hash: Base58CryptoHash,
+    },
+    Mint {
+        account_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    },
+    GrantRole {
+        role: Role,
+        account_id: AccountId,
+    },
+    RevokeRole {
+        role: Role,
+        account_id: AccountId,
+    },
+    SetMigrationWindow {
+        start: U64,
+        end: Option<U64>,
+    },
+    AddMigrationSource {
+        token_id: AccountId,
+        numerator: U128,
+        denominator: U128,
+        cap: Option<U128>,
+    },
+    SetMigrationSourceEnabled {
+        token_id: AccountId,
+        enabled: bool,
+    },
+    SetMigrationRatio {
+        token_id: AccountId,
+        numerator: U128,
+        denominator: U128,
+    },
+    SetMigrationCap {
+        token_id: AccountId,
+        cap: Option<U128>,
+    },
+    SetTotalMigrationCap {
+        cap: Option<U128>,
+    },
+    SetMultisig {
+        members: Vec<AccountId>,
+        threshold: u32,
+    },
+    /// Accepts ownership that has been proposed to this contract, which hands all owner
+    /// and role privileges to the multisig.
+    AcceptOwner,
+    /// Calls any other method of this contract without deposit, e.g. owner methods
+    /// that have no dedicated action. Callbacks can't be called.
+    ///
+    /// The call comes from this contract and attaches no deposit, so methods that require
+    /// a deposit fail, e.g. `fund_staking_rewards`. Methods that take tokens from the caller
+    /// take them from this contract's own balance, e.g. `create_vesting` without minting.
+    Call {
+        method_name: String,
+        args: Base64VecU8,
+    },
+}
+
+impl MultisigAction {
+    /// Returns the method and arguments this action calls on the contract.
+    fn method_call(&self) -> (String, Vec<u8>) {
+        let (method_name, args) = match self {
+            Self::Call { method_name, args } => return (method_name.clone(), args.0.clone()),
+            Self::DeployStaged { .. } => ("deploy_staged", json!({})),
+            Self::Mint {
+                account_id,
+                amount,
+                memo,
+            } => (
+                "mint",
+                json!({ "account_id": account_id, "amount": amount, "memo": memo }),
+            ),
+            Self::GrantRole { role, account_id } => (
+                "grant_role",
+                json!({ "role": role, "account_id": account_id }),
+            ),
+            Self::RevokeRole { role, account_id } => (
+                "revoke_role",
+                json!({ "role": role, "account_id": account_id }),
+            ),
+            Self::SetMigrationWindow { start, end } => (
+                "set_migration_window",
+                json!({ "start": start, "end": end }),
+            ),
+            Self::AddMigrationSource {
+                token_id,
+                numerator,
+                denominator,
+                cap,
+            } => (
+                "add_migration_source",
+                json!({
+                    "token_id": token_id,
+                    "numerator": numerator,
+                    "denominator": denominator,
+                    "cap": cap,
+                }),
+            ),
+            Self::SetMigrationSourceEnabled { token_id, enabled } => (
+                "set_migration_source_enabled",
+                json!({ "token_id": token_id, "enabled": enabled }),
+            ),
+            Self::SetMigrationRatio {
+                token_id,
+                numerator,
+                denominator,
+            } => (
+                "set_migration_ratio",
+                json!({
+                    "token_id": token_id,
+                    "numerator": numerator,
+                    "denominator": denominator,
+                }),
+            ),
+            Self::SetMigrationCap { token_id, cap } => (
+                "set_migration_cap",
+                json!({ "token_id": token_id, "cap": cap }),
+            ),
+            Self::SetTotalMigrationCap { cap } => {
+                ("set_total_migration_cap", json!({ "cap": cap }))
+            }
+            Self::SetMultisig { members, threshold } => (
+                "set_multisig",
+                json!({ "members": members, "threshold": threshold }),
+            ),
+            Self::AcceptOwner => ("accept_owner", json!({})),
+        };
+        (method_name.to_string(), args.to_string().into_bytes())
+    }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct MultisigProposal {
+    pub action: MultisigAction,
+    pub proposer_id: AccountId,
+    pub confirmations: BTreeSet<AccountId>,
+    /// Timestamp in nanoseconds after which the proposal can no longer be confirmed or executed.
+    pub expires_at: U64,
+    /// Whether the action is currently being executed. The proposal is removed once it has
+    /// succeeded and can be executed again if it has failed.
+    pub executing: bool,
+}
+
+#[near(serializers = [borsh])]
+pub struct Multisig {
+    members: BTreeSet<AccountId>,
+    /// Number of member confirmations required to execute a proposal.
+    /// Zero while no members have been configured.
+    threshold: u32,
+    /// Duration in nanoseconds after which proposals expire.
+    proposal_duration: u64,
+    next_proposal_id: u64,
+    proposals: IterableMap<u64, MultisigProposal>,
+}
+
+impl Multisig {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self {
+            members: BTreeSet::new(),
+            threshold: 0,
+            proposal_duration: DEFAULT_PROPOSAL_DURATION,
+            next_proposal_id: 0,
+            proposals: IterableMap::new(prefix),
+        }
+    }
+
+    /// Number of confirmations from accounts that are still members.
+    fn confirmation_count(&self, proposal: &MultisigProposal) -> u32 {
+        proposal
+            .confirmations
+            .iter()
+            .filter(|account_id| self.members.contains(*account_id))
+            .count() as u32
+    }
+}
+
+#[near(serializers = [json])]
+pub struct MultisigConfig {
+    pub members: Vec<AccountId>,
+    pub threshold: u32,
+    pub proposal_duration: U64,
+}
+
+#[near(serializers = [json])]
+pub struct MultisigProposalView {
+    pub id: U64,
+    #[serde(flatten)]
+    pub proposal: MultisigProposal,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the multisig members and how many of them need to confirm a proposal.
+    /// Pending proposals keep the confirmations of accounts that are still members.
+    /// Once members are configured, only the multisig itself can change them.
+    pub fn set_multisig(&mut self, members: Vec<AccountId>, threshold: u32) {
+        self.assert_multisig_admin();
+        let members: BTreeSet<_> = members.into_iter().collect();
+        require!(
+            threshold > 0 && threshold as usize <= members.len(),
+            "Invalid multisig threshold"
+        );
+        self.multisig.members = members;
+        self.multisig.threshold = threshold;
+    }
+
+    pub fn set_multisig_proposal_duration(&mut self, duration: U64) {
+        self.assert_multisig_admin();
+        self.multisig.proposal_duration = duration.0;
+    }
+
+    /// Proposes an action, which is confirmed by the proposer right away.
+    pub fn multisig_propose(&mut self, action: MultisigAction) -> U64 {
+        let account_id = self.assert_multisig_member();
+        if let MultisigAction::Call { method_name, .. } = &action {
+            require!(
+                !is_callback_method(method_name),
+                "Method can't be called by the multisig"
+            );
+        }
+        let proposal_id = self.multisig.next_proposal_id;
+        self.multisig.next_proposal_id += 1;
+
+        Event::MultisigProposed {
+            proposal_id: proposal_id.into(),
+            proposer_id: &account_id,
+            action: &action,
+        }
+        .emit();
+        self.multisig.proposals.insert(
+            proposal_id,
+            MultisigProposal {
+                action,
+                proposer_id: account_id.clone(),
+                confirmations: BTreeSet::from([account_id]),
+                expires_at: (env::block_timestamp() + self.multisig.proposal_duration).into(),
+                executing: false,
+            },
+        );
+        proposal_id.into()
+    }
+
+    pub fn multisig_confirm(&mut self, proposal_id: U64) {
+        let account_id = self.assert_multisig_member();
+        let proposal = self.internal_pending_proposal(proposal_id.0);
+        require!(
+            proposal.confirmations.insert(account_id.clone()),
+            "Proposal already confirmed"
+        );
+        Event::MultisigConfirmed {
+            proposal_id,
+            account_id: &account_id,
+        }
+        .emit();
+    }
+
+    /// Revokes a confirmation of the caller.
+    pub fn multisig_revoke(&mut self, proposal_id: U64) {
+        let account_id = self.assert_multisig_member();
+        let proposal = self.internal_pending_proposal(proposal_id.0);
+        require!(
+            proposal.confirmations.remove(&account_id),
+            "Proposal not confirmed"
+        );
+        Event::MultisigRevoked {
+            proposal_id,
+            account_id: &account_id,
+        }
+        .emit();
+    }
+
+    /// Executes a proposal that has been confirmed by enough members.
+    /// Resolves to whether the action succeeded. Failed actions can be executed again.
+    pub fn multisig_execute(&mut self, proposal_id: U64) -> Promise {
+        let account_id = self.assert_multisig_member();
+        let proposal = self.internal_pending_proposal(proposal_id.0);
+        require!(!proposal.executing, "Proposal is already being executed");
+        proposal.executing = true;
+        let proposal = proposal.clone();
+        require!(
+            self.multisig.confirmation_count(&proposal) >= self.multisig.threshold,
+            "Not enough confirmations"
+        );
+        if let MultisigAction::DeployStaged { code_hash } = &proposal.action {
+            require!(
+                self.staged_code_hash().as_ref() == Some(code_hash),
+                "Staged code does not match proposal"
+            );
+        }
+
+        let (method_name, args) = proposal.action.method_call();
+        Promise::new(env::current_account_id())
+            .function_call_weight(
+                method_name,
+                args,
+                NearToken::from_yoctonear(0),
+                Gas::from_gas(0),
+                GasWeight(1),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_EXECUTION)
+                    .on_multisig_executed(proposal_id, account_id),
+            )
+    }
+
+    /// Removes the proposal if its action succeeded and allows to execute it again otherwise.
+    #[private]
+    pub fn on_multisig_executed(&mut self, proposal_id: U64, account_id: AccountId) -> bool {
+        let success = near_sdk::is_promise_success();
+        if success {
+            self.multisig.proposals.remove(&proposal_id.0);
+            Event::MultisigExecuted {
+                proposal_id,
+                account_id: &account_id,
+            }
+            .emit();
+        } else if let Some(proposal) = self.multisig.proposals.get_mut(&proposal_id.0) {
+            proposal.executing = false;
+            Event::MultisigExecutionFailed {
+                proposal_id,
+                account_id: &account_id,
+            }
+            .emit();
+        }
+        success
+    }
+
+    /// Removes an expired proposal. Anyone can clean up expired proposals.
+    pub fn multisig_remove_expired(&mut self, proposal_id: U64) {
+        let proposal = self
+            .multisig
+            .proposals
+            .get(&proposal_id.0)
+            .unwrap_or_else(|| env::panic_str("Unknown proposal"));
+        require!(
+            proposal.expires_at.0 <= env::block_timestamp(),
+            "Proposal has not expired"
+        );
+        self.multisig.proposals.remove(&proposal_id.0);
+    }
+
+    pub fn multisig_config(&self) -> MultisigConfig {
+        MultisigConfig {
+            members: self.multisig.members.iter().cloned().collect(),
+            threshold: self.multisig.threshold,
+            proposal_duration: self.multisig.proposal_duration.into(),
+        }
+    }
+
+    pub fn multisig_proposal(&self, proposal_id: U64) -> Option<MultisigProposal> {
+        self.multisig.proposals.get(&proposal_id.0).cloned()
+    }
+
+    /// Lists all proposals that have not expired yet.
+    pub fn multisig_pending_proposals(&self) -> Vec<MultisigProposalView> {
+        let now = env::block_timestamp();
+        self.multisig
+            .proposals
+            .iter()
+            .filter(|(_, proposal)| proposal.expires_at.0 > now)
+            .map(|(id, proposal)| MultisigProposalView {
+                id: (*id).into(),
+                proposal: proposal.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    /// The owner configures the multisig until it has members and the multisig itself afterwards.
+    fn assert_multisig_admin(&self) {
+        if self.multisig.members.is_empty() {
+            self.assert_owner();
+        } else {
+            require!(
+                env::predecessor_account_id() == env::current_account_id(),
+                "Only the multisig can change its configuration"
+            );
+        }
+    }
+
+    fn assert_multisig_member(&self) -> AccountId {
+        let account_id = env::predecessor_account_id();
+        require!(
+            self.multisig.members.contains(&account_id),
+            "Only multisig members can call this method"
+        );
+        account_id
+    }
+
+    fn internal_pending_proposal(&mut self, proposal_id: u64) -> &mut MultisigProposal {
+        let proposal = self
+            .multisig
+            .proposals
+            .get_mut(&proposal_id)
+            .unwrap_or_else(|| env::panic_str("Unknown proposal"));
+        require!(
+            proposal.expires_at.0 > env::block_timestamp(),
+            "Proposal has expired"
+        );
+        proposal
+    }
+}
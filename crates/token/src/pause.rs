@@ -0,0 +1,84 @@
+use crate::{event::Event, Contract, ContractExt, Role};
+use near_sdk::{env, near, near_bindgen, require};
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseFeature {
+    /// `ft_transfer`, `ft_transfer_call` and `ft_burn`.
+    Transfers,
+    /// Migrating and redeeming tokens.
+    Migration,
+    /// Minting by minters.
+    Minting,
+    /// Registering and unregistering storage.
+    Storage,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct PauseStatus {
+    pub transfers: bool,
+    pub migration: bool,
+    pub minting: bool,
+    pub storage: bool,
+}
+
+impl PauseStatus {
+    pub fn get(&self, feature: PauseFeature) -> bool {
+        match feature {
+            PauseFeature::Transfers => self.transfers,
+            PauseFeature::Migration => self.migration,
+            PauseFeature::Minting => self.minting,
+            PauseFeature::Storage => self.storage,
+        }
+    }
+
+    fn get_mut(&mut self, feature: PauseFeature) -> &mut bool {
+        match feature {
+            PauseFeature::Transfers => &mut self.transfers,
+            PauseFeature::Migration => &mut self.migration,
+            PauseFeature::Minting => &mut self.minting,
+            PauseFeature::Storage => &mut self.storage,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn pause(&mut self, feature: PauseFeature) {
+        self.internal_set_paused(feature, true);
+    }
+
+    pub fn unpause(&mut self, feature: PauseFeature) {
+        self.internal_set_paused(feature, false);
+    }
+
+    pub fn pause_status(&self) -> PauseStatus {
+        self.paused.clone()
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_not_paused(&self, feature: PauseFeature) {
+        require!(
+            !self.paused.get(feature),
+            format!("{:?} is paused", feature)
+        );
+    }
+
+    fn internal_set_paused(&mut self, feature: PauseFeature, paused: bool) {
+        self.assert_role(Role::Pauser);
+        let current = self.paused.get_mut(feature);
+        if *current == paused {
+            return;
+        }
+        *current = paused;
+        Event::PauseChanged {
+            feature,
+            paused,
+            account_id: &env::predecessor_account_id(),
+        }
+        .emit();
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{event::Event, Contract, ContractExt, TransferIntent};
+use near_sdk::{
+    borsh, env,
+    json_types::{Base64VecU8, U128, U64},
+    near, near_bindgen, require, AccountId, CurveType, PublicKey,
+};
+
+/// Allowance that `owner_id` signed off-chain and that anyone can submit via `ft_permit`.
+///
+/// Signing without any transaction of the owner is only supported for implicit accounts.
+/// Named accounts have to call `set_signing_key` once themselves, paying its gas and storage,
+/// because this contract can't verify their access keys.
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct Permit {
+    pub owner_id: AccountId,
+    pub spender_id: AccountId,
+    pub amount: U128,
+    /// Has to match the current nonce of `owner_id`.
+    pub nonce: U64,
+    /// Timestamp in nanoseconds after which the permit can no longer be used.
+    pub deadline: U64,
+}
+
+/// Message that is signed off-chain. The signed bytes are the borsh serialization of
+/// this token's account ID followed by the message.
+#[near(serializers = [borsh])]
+pub enum SignedMessage {
+    Permit(Permit),
+    TransferIntent(TransferIntent),
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the allowance of a permit signed by its owner. The caller pays the storage
+    /// of a new allowance and of the owner's nonce and gets the rest of the deposit refunded.
+    #[payable]
+    pub fn ft_permit(&mut self, permit: Permit, signature: Base64VecU8) {
+        require!(
+            permit.owner_id != permit.spender_id,
+            "Can't approve yourself"
+        );
+        require!(
+            env::block_timestamp() <= permit.deadline.0,
+            "Permit is expired"
+        );
+        self.assert_not_blocked(&permit.owner_id);
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_use_nonce(&permit.owner_id, permit.nonce.0);
+        self.internal_verify_signature(
+            &permit.owner_id,
+            &SignedMessage::Permit(permit.clone()),
+            &signature.0,
+        );
+        self.allowances.insert(
+            (permit.owner_id.clone(), permit.spender_id.clone()),
+            permit.amount.0,
+        );
+        self.allowances.flush();
+        self.nonces.flush();
+
+        self.internal_settle_storage(&env::predecessor_account_id(), initial_storage_usage);
+        Event::Approve {
+            owner_id: &permit.owner_id,
+            spender_id: &permit.spender_id,
+            amount: permit.amount,
+        }
+        .emit();
+    }
+
+    /// Sets the ed25519 key that signs messages for the caller. Named accounts need it before
+    /// they can sign, while implicit accounts can always sign with the key that their account ID
+    /// is derived from.
+    #[payable]
+    pub fn set_signing_key(&mut self, public_key: Option<PublicKey>) {
+        let account_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        if let Some(public_key) = public_key {
+            require!(
+                public_key.curve_type() == CurveType::ED25519,
+                "Only ed25519 keys are supported"
+            );
+            self.signing_keys.insert(account_id.clone(), public_key);
+        } else {
+            self.signing_keys.remove(&account_id);
+        }
+        self.signing_keys.flush();
+
+        self.internal_settle_storage(&account_id, initial_storage_usage);
+    }
+
+    pub fn signing_key_of(&self, account_id: AccountId) -> Option<PublicKey> {
+        self.signing_keys.get(&account_id).cloned()
+    }
+
+    /// Returns the nonce that the next signed message of `account_id` has to use.
+    pub fn nonce_of(&self, account_id: AccountId) -> U64 {
+        self.nonces
+            .get(&account_id)
+            .copied()
+            .unwrap_or_default()
+            .into()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_use_nonce(&mut self, account_id: &AccountId, nonce: u64) {
+        let current = self.nonces.entry(account_id.clone()).or_default();
+        require!(*current == nonce, "Invalid nonce");
+        *current += 1;
+    }
+
+    pub(crate) fn internal_verify_signature(
+        &self,
+        account_id: &AccountId,
+        message: &SignedMessage,
+        signature: &[u8],
+    ) {
+        let public_key = match self.signing_keys.get(account_id) {
+            Some(public_key) => public_key.as_bytes()[1..].try_into().unwrap(),
+            None => implicit_public_key(account_id)
+                .unwrap_or_else(|| env::panic_str("Account has no signing key")),
+        };
+        let signature: &[u8; 64] = signature
+            .try_into()
+            .unwrap_or_else(|_| env::panic_str("Invalid signature length"));
+        let message = borsh::to_vec(&(env::current_account_id(), message)).unwrap();
+        require!(
+            env::ed25519_verify(signature, &message, &public_key),
+            "Invalid signature"
+        );
+    }
+}
+
+/// Decodes the ed25519 public key of a NEAR implicit account.
+fn implicit_public_key(account_id: &AccountId) -> Option<[u8; 32]> {
+    let hex = account_id.as_bytes();
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut public_key = [0; 32];
+    for (byte, chunk) in public_key.iter_mut().zip(hex.chunks(2)) {
+        let chunk = std::str::from_utf8(chunk).ok()?;
+        *byte = u8::from_str_radix(chunk, 16).ok()?;
+    }
+    Some(public_key)
+}
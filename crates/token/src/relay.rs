@@ -0,0 +1,87 @@
+use crate::{Contract, ContractExt, SignedMessage};
+use near_sdk::{
+    env,
+    json_types::{Base64VecU8, U128, U64},
+    near, near_bindgen, require, AccountId,
+};
+
+/// Transfer that `sender_id` signed off-chain and that a relayer can submit via
+/// `ft_transfer_signed` in exchange for `fee`. Like permits, it can only be signed without
+/// a transaction of the sender if the sender is an implicit account.
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct TransferIntent {
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub amount: U128,
+    /// Amount of tokens that the relayer receives from `sender_id`.
+    pub fee: U128,
+    /// Has to match the current nonce of `sender_id`.
+    pub nonce: U64,
+    /// Timestamp in nanoseconds after which the intent can no longer be executed.
+    pub expires_at: U64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub enum TransferIntentStatus {
+    /// Can be executed with a valid signature.
+    Executable,
+    Expired,
+    /// The nonce has already been used by this or another signed message.
+    NonceUsed,
+    /// Signed messages with lower nonces have to be executed first.
+    NonceTooHigh,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Executes a transfer signed by its sender and pays the fee to the caller. The caller
+    /// pays the storage of the sender's nonce and gets the rest of the deposit refunded.
+    #[payable]
+    pub fn ft_transfer_signed(&mut self, intent: TransferIntent, signature: Base64VecU8) {
+        require!(
+            env::block_timestamp() <= intent.expires_at.0,
+            "Transfer intent is expired"
+        );
+        let relayer_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_use_nonce(&intent.sender_id, intent.nonce.0);
+        self.internal_verify_signature(
+            &intent.sender_id,
+            &SignedMessage::TransferIntent(intent.clone()),
+            &signature.0,
+        );
+        self.nonces.flush();
+        self.internal_settle_storage(&relayer_id, initial_storage_usage);
+
+        self.internal_transfer(
+            &intent.sender_id,
+            &intent.receiver_id,
+            intent.amount.0,
+            None,
+        );
+        if intent.fee.0 > 0 {
+            self.internal_transfer(
+                &intent.sender_id,
+                &relayer_id,
+                intent.fee.0,
+                Some("relayer fee".to_string()),
+            );
+        }
+    }
+
+    pub fn transfer_intent_status(&self, intent: TransferIntent) -> TransferIntentStatus {
+        let nonce = self.nonce_of(intent.sender_id).0;
+        if intent.nonce.0 < nonce {
+            TransferIntentStatus::NonceUsed
+        } else if env::block_timestamp() > intent.expires_at.0 {
+            TransferIntentStatus::Expired
+        } else if intent.nonce.0 > nonce {
+            TransferIntentStatus::NonceTooHigh
+        } else {
+            TransferIntentStatus::Executable
+        }
+    }
+}
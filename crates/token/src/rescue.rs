@@ -0,0 +1,97 @@
+use crate::{event::Event, Contract, ContractExt};
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{env, json_types::U128, near_bindgen, require, AccountId, Gas, NearToken, Promise};
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_RESCUE: Gas = Gas::from_tgas(5);
+
+#[near_bindgen]
+impl Contract {
+    /// Sends `amount` of a NEP-141 token held by this contract to `receiver_id`.
+    /// Tokens of migration sources are excluded and need to be swept instead.
+    pub fn rescue_tokens(
+        &mut self,
+        token_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> Promise {
+        self.assert_owner();
+        require!(
+            token_id != env::current_account_id(),
+            "Use `return_self_held_tokens` to rescue JLU"
+        );
+        require!(
+            !self.migration_sources.contains_key(&token_id),
+            "Migration source tokens can only be swept"
+        );
+
+        ext_ft_core::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver_id.clone(), amount, Some("JLU rescue".to_string()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_RESCUE)
+                    .on_rescue_tokens(token_id, receiver_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_rescue_tokens(
+        &mut self,
+        token_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> bool {
+        let rescued = near_sdk::is_promise_success();
+        if rescued {
+            Event::TokensRescued {
+                token_id: &token_id,
+                receiver_id: &receiver_id,
+                amount,
+            }
+            .emit();
+        }
+        rescued
+    }
+
+    /// Sends JLU that has been transferred to this contract's own account to `receiver_id`.
+    pub fn return_self_held_tokens(&mut self, receiver_id: AccountId, amount: U128) {
+        self.assert_owner();
+        self.assert_not_blocked(&receiver_id);
+        require!(
+            amount.0 <= self.internal_self_held_balance(),
+            "Not enough self held tokens"
+        );
+        let contract_id = env::current_account_id();
+        self.internal_checkpoint(&contract_id);
+        self.internal_checkpoint(&receiver_id);
+        self.token.internal_transfer(
+            &contract_id,
+            &receiver_id,
+            amount.0,
+            Some("JLU rescue".to_string()),
+        );
+        Event::TokensRescued {
+            token_id: &contract_id,
+            receiver_id: &receiver_id,
+            amount,
+        }
+        .emit();
+    }
+
+    /// Amount of JLU held by this contract's own account that can be returned.
+    pub fn self_held_balance(&self) -> U128 {
+        self.internal_self_held_balance().into()
+    }
+}
+
+impl Contract {
+    fn internal_self_held_balance(&self) -> u128 {
+        self.token
+            .accounts
+            .get(&env::current_account_id())
+            .unwrap_or_default()
+            .saturating_sub(self.vesting_reserved() + self.staking_reserved())
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{Contract, ContractExt};
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    near, near_bindgen, require,
+    store::{LookupMap, Vector},
+    AccountId, BlockHeight, IntoStorageKey,
+};
+use std::ops::Range;
+
+/// Number of checkpoints kept per account. Older ones are pruned, so that dust transfers
+/// can't grow the storage of this contract without bound. Voting doesn't depend on them,
+/// because balances at the snapshot blocks of proposals in voting are recorded separately.
+pub const MAX_ACCOUNT_CHECKPOINTS: u32 = 32;
+/// Number of total supply checkpoints kept.
+pub const MAX_SUPPLY_CHECKPOINTS: u32 = 1024;
+
+/// Balance at the start of `block_height`, recorded before it first changes in that block.
+/// It is also the balance at the end of every block since the previous checkpoint.
+#[near(serializers = [borsh])]
+#[derive(Clone, Copy)]
+pub struct Checkpoint {
+    block_height: BlockHeight,
+    balance: u128,
+}
+
+#[near(serializers = [borsh])]
+pub struct Checkpoints {
+    /// Number of checkpoints ever recorded for each account.
+    /// Only the last `MAX_ACCOUNT_CHECKPOINTS` of them are kept.
+    lengths: LookupMap<AccountId, u32>,
+    entries: LookupMap<(AccountId, u32), Checkpoint>,
+    /// Ring buffer of the last `MAX_SUPPLY_CHECKPOINTS` total supply checkpoints.
+    total_supply: Vector<Checkpoint>,
+    /// Balances of each account at the snapshot blocks of proposals in voting, recorded before
+    /// the balance first changes after the proposal has been created. They are not pruned.
+    snapshot_balances: LookupMap<AccountId, Vec<(BlockHeight, u128)>>,
+}
+
+impl Checkpoints {
+    pub fn new<L, E, S, B>(
+        lengths_prefix: L,
+        entries_prefix: E,
+        total_supply_prefix: S,
+        snapshot_balances_prefix: B,
+    ) -> Self
+    where
+        L: IntoStorageKey,
+        E: IntoStorageKey,
+        S: IntoStorageKey,
+        B: IntoStorageKey,
+    {
+        Self {
+            lengths: LookupMap::new(lengths_prefix),
+            entries: LookupMap::new(entries_prefix),
+            total_supply: Vector::new(total_supply_prefix),
+            snapshot_balances: LookupMap::new(snapshot_balances_prefix),
+        }
+    }
+
+    fn account_checkpoint(&self, account_id: &AccountId, index: u32) -> Checkpoint {
+        self.entries[&(account_id.clone(), index)]
+    }
+
+    /// Index of the oldest total supply checkpoint, which is the one with the lowest block height.
+    fn oldest_supply_index(&self) -> u32 {
+        let total_supply = &self.total_supply;
+        if total_supply.len() < MAX_SUPPLY_CHECKPOINTS {
+            return 0;
+        }
+        let (mut low, mut high) = (0, total_supply.len() - 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if total_supply[mid].block_height > total_supply[high].block_height {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+/// Returns the balance of the first checkpoint in `range` after `block_height` or `None` if there
+/// is none, in which case the balance hasn't changed since then. If older checkpoints have been
+/// pruned, `block_height` has to be after the first one in `range`.
+fn find_balance_at(
+    range: Range<u32>,
+    pruned: bool,
+    block_height: BlockHeight,
+    get: impl Fn(u32) -> Checkpoint,
+) -> Option<u128> {
+    let (mut low, mut high) = (range.start, range.end);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if get(mid).block_height > block_height {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    require!(
+        !pruned || low > range.start,
+        "Balance history of this block has been pruned"
+    );
+    (low < range.end).then(|| get(low).balance)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Balance of `account_id` at the end of `block_height`.
+    /// Fails if the account has changed its balance in more than `MAX_ACCOUNT_CHECKPOINTS`
+    /// blocks since then. The history of an account is removed when it unregisters.
+    pub fn ft_balance_of_at(&self, account_id: AccountId, block_height: U64) -> U128 {
+        self.internal_balance_of_at(&account_id, block_height.0)
+            .into()
+    }
+
+    /// Total supply at the end of `block_height`.
+    /// Fails if the total supply has changed in more than `MAX_SUPPLY_CHECKPOINTS` blocks since then.
+    pub fn ft_total_supply_at(&self, block_height: U64) -> U128 {
+        let total_supply = &self.checkpoints.total_supply;
+        let len = total_supply.len();
+        let oldest = self.checkpoints.oldest_supply_index();
+        find_balance_at(
+            0..len,
+            len == MAX_SUPPLY_CHECKPOINTS,
+            block_height.0,
+            |index| total_supply[(oldest + index) % len],
+        )
+        .unwrap_or(self.token.total_supply)
+        .into()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_balance_of_at(
+        &self,
+        account_id: &AccountId,
+        block_height: BlockHeight,
+    ) -> u128 {
+        let len = self
+            .checkpoints
+            .lengths
+            .get(account_id)
+            .copied()
+            .unwrap_or_default();
+        let first = len.saturating_sub(MAX_ACCOUNT_CHECKPOINTS);
+        find_balance_at(first..len, first > 0, block_height, |index| {
+            self.checkpoints.account_checkpoint(account_id, index)
+        })
+        .unwrap_or_else(|| self.token.accounts.get(account_id).unwrap_or_default())
+    }
+
+    /// Balance of `account_id` at the snapshot block of a proposal in voting. Without a recorded
+    /// balance, the account has changed its balance at most in the block the proposal has been
+    /// created in, whose checkpoint can't have been pruned yet.
+    pub(crate) fn internal_snapshot_balance(
+        &self,
+        account_id: &AccountId,
+        snapshot_block: BlockHeight,
+    ) -> u128 {
+        self.checkpoints
+            .snapshot_balances
+            .get(account_id)
+            .and_then(|balances| balances.iter().find(|(block, _)| *block == snapshot_block))
+            .map(|(_, balance)| *balance)
+            .unwrap_or_else(|| self.internal_balance_of_at(account_id, snapshot_block))
+    }
+
+    /// Records the balance of `account_id` before it changes for the first time in this block
+    /// and prunes its oldest checkpoint once there are `MAX_ACCOUNT_CHECKPOINTS` of them.
+    /// Needs to be called before every change of the account's balance.
+    pub(crate) fn internal_checkpoint(&mut self, account_id: &AccountId) {
+        self.internal_record_snapshot_balances(account_id);
+        let block_height = env::block_height();
+        let len = self
+            .checkpoints
+            .lengths
+            .get(account_id)
+            .copied()
+            .unwrap_or_default();
+        if len > 0
+            && self
+                .checkpoints
+                .account_checkpoint(account_id, len - 1)
+                .block_height
+                == block_height
+        {
+            return;
+        }
+        if len >= MAX_ACCOUNT_CHECKPOINTS {
+            self.checkpoints
+                .entries
+                .remove(&(account_id.clone(), len - MAX_ACCOUNT_CHECKPOINTS));
+        }
+        let balance = self.token.accounts.get(account_id).unwrap_or_default();
+        self.checkpoints.entries.insert(
+            (account_id.clone(), len),
+            Checkpoint {
+                block_height,
+                balance,
+            },
+        );
+        self.checkpoints.lengths.insert(account_id.clone(), len + 1);
+    }
+
+    /// Removes the balance history of an account that has unregistered, whose storage
+    /// is no longer paid for.
+    pub(crate) fn internal_remove_checkpoints(&mut self, account_id: &AccountId) {
+        let len = self
+            .checkpoints
+            .lengths
+            .remove(account_id)
+            .unwrap_or_default();
+        for index in len.saturating_sub(MAX_ACCOUNT_CHECKPOINTS)..len {
+            self.checkpoints
+                .entries
+                .remove(&(account_id.clone(), index));
+        }
+        self.checkpoints.snapshot_balances.remove(account_id);
+    }
+
+    /// Records the balance of `account_id` at the snapshot blocks of proposals in voting
+    /// that it hasn't been recorded for yet, and drops those of proposals no longer in voting.
+    fn internal_record_snapshot_balances(&mut self, account_id: &AccountId) {
+        let snapshot_blocks = self.governance.voting_snapshot_blocks();
+        if snapshot_blocks.is_empty() {
+            return;
+        }
+        let mut balances = self
+            .checkpoints
+            .snapshot_balances
+            .get(account_id)
+            .cloned()
+            .unwrap_or_default();
+        let recorded = balances.len();
+        balances.retain(|(block, _)| snapshot_blocks.contains(block));
+        let mut changed = balances.len() != recorded;
+        for snapshot_block in snapshot_blocks {
+            if !balances.iter().any(|(block, _)| *block == snapshot_block) {
+                let balance = self.internal_balance_of_at(account_id, snapshot_block);
+                balances.push((snapshot_block, balance));
+                changed = true;
+            }
+        }
+        if changed {
+            self.checkpoints
+                .snapshot_balances
+                .insert(account_id.clone(), balances);
+        }
+    }
+
+    /// Records the total supply before it changes for the first time in this block,
+    /// replacing the oldest checkpoint once there are `MAX_SUPPLY_CHECKPOINTS` of them.
+    /// Needs to be called before every mint and burn.
+    pub(crate) fn internal_checkpoint_supply(&mut self) {
+        let block_height = env::block_height();
+        let oldest = self.checkpoints.oldest_supply_index();
+        let total_supply = &mut self.checkpoints.total_supply;
+        let len = total_supply.len();
+        if len > 0 && total_supply[(oldest + len - 1) % len].block_height == block_height {
+            return;
+        }
+        let checkpoint = Checkpoint {
+            block_height,
+            balance: self.token.total_supply,
+        };
+        if len < MAX_SUPPLY_CHECKPOINTS {
+            total_supply.push(checkpoint);
+        } else {
+            total_supply[oldest] = checkpoint;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{event::Event, Contract, ContractExt, Role};
+use near_contract_standards::{fungible_token::Balance, storage_management::StorageManagement};
+use near_sdk::{
+    assert_one_yocto, env, near, near_bindgen, require, store::LookupSet, AccountId,
+    IntoStorageKey, NearToken, Promise,
+};
+
+/// One day in nanoseconds.
+const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Pool of NEAR funded by the owner to pay storage of unregistered token receivers.
+#[near(serializers = [borsh])]
+pub struct Sponsorship {
+    enabled: bool,
+    /// NEAR that is still available for sponsoring registrations.
+    pool: NearToken,
+    /// Maximum number of sponsored registrations per day.
+    daily_limit: u32,
+    /// Day since the Unix epoch, for which `registered_today` is counted.
+    day: u64,
+    registered_today: u32,
+    /// Accounts whose storage has been paid from the pool.
+    accounts: LookupSet<AccountId>,
+}
+
+impl Sponsorship {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self {
+            enabled: false,
+            pool: NearToken::from_yoctonear(0),
+            daily_limit: 0,
+            day: 0,
+            registered_today: 0,
+            accounts: LookupSet::new(prefix),
+        }
+    }
+
+    fn registered_today(&self) -> u32 {
+        if self.day == env::block_timestamp() / DAY {
+            self.registered_today
+        } else {
+            0
+        }
+    }
+}
+
+#[near(serializers = [json])]
+pub struct SponsorshipStatus {
+    pub enabled: bool,
+    pub pool: NearToken,
+    pub daily_limit: u32,
+    /// Number of registrations that can still be sponsored today.
+    pub remaining_today: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Adds the attached deposit to the pool for sponsoring storage.
+    #[payable]
+    pub fn fund_sponsorship(&mut self) {
+        self.assert_owner();
+        self.sponsorship.pool = self
+            .sponsorship
+            .pool
+            .saturating_add(env::attached_deposit());
+    }
+
+    /// Withdraws NEAR from the sponsorship pool to the caller. Withdraws everything without `amount`.
+    pub fn withdraw_sponsorship(&mut self, amount: Option<NearToken>) -> Promise {
+        self.assert_owner();
+        let amount = amount.unwrap_or(self.sponsorship.pool);
+        self.sponsorship.pool = self
+            .sponsorship
+            .pool
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Not enough NEAR in sponsorship pool"));
+        Promise::new(env::predecessor_account_id()).transfer(amount)
+    }
+
+    /// Enables or disables sponsored registration of receivers of transfers from distributors
+    /// and sets how many registrations can be sponsored per day.
+    pub fn set_sponsorship(&mut self, enabled: bool, daily_limit: u32) {
+        self.assert_owner();
+        self.sponsorship.enabled = enabled;
+        self.sponsorship.daily_limit = daily_limit;
+    }
+
+    pub fn sponsorship_status(&self) -> SponsorshipStatus {
+        SponsorshipStatus {
+            enabled: self.sponsorship.enabled,
+            pool: self.sponsorship.pool,
+            daily_limit: self.sponsorship.daily_limit,
+            remaining_today: self
+                .sponsorship
+                .daily_limit
+                .saturating_sub(self.sponsorship.registered_today()),
+        }
+    }
+}
+
+impl Contract {
+    /// Registers `account_id` with storage paid from the sponsorship pool, if it isn't registered
+    /// yet, the tokens are sent by a distributor and sponsoring is possible. Transfers from other
+    /// accounts never register receivers, so that they can't drain the pool with dust transfers.
+    pub(crate) fn internal_sponsor_registration(
+        &mut self,
+        sender_id: &AccountId,
+        account_id: &AccountId,
+    ) {
+        if self.token.accounts.contains_key(account_id)
+            || !self.sponsorship.enabled
+            || !self.internal_has_role(Role::Distributor, sender_id)
+        {
+            return;
+        }
+        let registered_today = self.sponsorship.registered_today();
+        if registered_today >= self.sponsorship.daily_limit {
+            return;
+        }
+        let Some(pool) = self
+            .sponsorship
+            .pool
+            .checked_sub(self.token.storage_balance_bounds().min)
+        else {
+            return;
+        };
+
+        self.sponsorship.pool = pool;
+        self.sponsorship.day = env::block_timestamp() / DAY;
+        self.sponsorship.registered_today = registered_today + 1;
+        self.sponsorship.accounts.insert(account_id.clone());
+        self.token.internal_register_account(account_id);
+        Event::StorageSponsored { account_id }.emit();
+    }
+
+    pub(crate) fn is_sponsored(&self, account_id: &AccountId) -> bool {
+        self.sponsorship.accounts.contains(account_id)
+    }
+
+    /// Unregisters a sponsored account of the caller.
+    /// The storage deposit is returned to the sponsorship pool instead of the account.
+    pub(crate) fn internal_sponsored_storage_unregister(
+        &mut self,
+        force: Option<bool>,
+    ) -> (AccountId, Balance) {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self.token.internal_unwrap_balance_of(&account_id);
+        require!(
+            balance == 0 || force.unwrap_or(false),
+            "Can't unregister the account with the positive balance without force"
+        );
+        self.token.accounts.remove(&account_id);
+        self.token.total_supply -= balance;
+        self.sponsorship.accounts.remove(&account_id);
+        self.sponsorship.pool = self
+            .sponsorship
+            .pool
+            .saturating_add(self.token.storage_balance_bounds().min);
+        (account_id, balance)
+    }
+}
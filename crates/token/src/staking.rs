@@ -0,0 +1,350 @@
+use crate::{event::Event, Contract, ContractExt};
+use near_sdk::{
+    assert_one_yocto, env,
+    json_types::{U128, U64},
+    near, near_bindgen, require,
+    store::LookupMap,
+    AccountId, IntoStorageKey,
+};
+
+/// Message of `ft_transfer_call` to this contract that stakes the transferred tokens.
+pub const STAKE_MSG: &str = "stake";
+
+/// Minimum amount that an account has staked, unless it has unstaked everything.
+/// Keeps dust stakes from filling storage with staker entries that this contract pays for.
+pub const MIN_STAKE: u128 = 1_000_000_000_000_000_000;
+
+/// Scale of the reward per staked token.
+const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000_000_000;
+
+mod u256 {
+    #![allow(clippy::assign_op_pattern)]
+
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+
+use u256::U256;
+
+#[near(serializers = [borsh])]
+#[derive(Default)]
+pub struct Staker {
+    staked: u128,
+    /// Reward per token at the last time that `rewards` was updated.
+    reward_per_token_paid: [u64; 4],
+    rewards: u128,
+    unstaking: u128,
+    /// Timestamp in nanoseconds at which `unstaking` can be withdrawn.
+    unstaking_until: u64,
+}
+
+#[near(serializers = [borsh])]
+pub struct Staking {
+    total_staked: u128,
+    /// Tokens that have been unstaked but are still in their cooldown.
+    total_unstaking: u128,
+    /// Tokens funded for rewards that have not been claimed yet.
+    reward_pool: u128,
+    /// Rewards emitted per second until `period_end`.
+    reward_rate: u128,
+    /// Accumulated rewards per staked token, scaled by `REWARD_PRECISION`.
+    reward_per_token: [u64; 4],
+    /// Timestamp in seconds of the last accumulator update.
+    last_update: u64,
+    /// Timestamp in seconds at which the current emission ends.
+    period_end: u64,
+    /// Duration in nanoseconds that unstaked tokens need to wait before being withdrawn.
+    unstake_cooldown: u64,
+    stakers: LookupMap<AccountId, Staker>,
+}
+
+impl Staking {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self {
+            total_staked: 0,
+            total_unstaking: 0,
+            reward_pool: 0,
+            reward_rate: 0,
+            reward_per_token: U256::zero().0,
+            last_update: 0,
+            period_end: 0,
+            unstake_cooldown: 0,
+            stakers: LookupMap::new(prefix),
+        }
+    }
+
+    /// Tokens held by this contract that belong to stakers or the reward pool.
+    pub fn reserved(&self) -> u128 {
+        self.total_staked + self.total_unstaking + self.reward_pool
+    }
+
+    fn reward_per_token(&self) -> U256 {
+        let reward_per_token = U256(self.reward_per_token);
+        let now = now_seconds().min(self.period_end);
+        if self.total_staked == 0 || now <= self.last_update {
+            return reward_per_token;
+        }
+        reward_per_token
+            + U256::from(self.reward_rate)
+                * U256::from(now - self.last_update)
+                * U256::from(REWARD_PRECISION)
+                / U256::from(self.total_staked)
+    }
+
+    /// Updates the accumulator. Rewards emitted while nothing is staked are released
+    /// from the reward pool.
+    fn update_reward_per_token(&mut self) -> U256 {
+        let reward_per_token = self.reward_per_token();
+        let now = now_seconds().min(self.period_end).max(self.last_update);
+        if self.total_staked == 0 {
+            let unclaimable = self.reward_rate * (now - self.last_update) as u128;
+            self.reward_pool = self.reward_pool.saturating_sub(unclaimable);
+        }
+        self.reward_per_token = reward_per_token.0;
+        self.last_update = now;
+        reward_per_token
+    }
+
+    /// Updates the accumulator and the rewards of `account_id`.
+    fn update_rewards(&mut self, account_id: &AccountId) -> &mut Staker {
+        let reward_per_token = self.update_reward_per_token();
+        let staker = self.stakers.entry(account_id.clone()).or_default();
+        staker.rewards = earned(staker, reward_per_token);
+        staker.reward_per_token_paid = reward_per_token.0;
+        staker
+    }
+}
+
+#[near(serializers = [json])]
+pub struct StakingInfo {
+    pub total_staked: U128,
+    pub reward_pool: U128,
+    pub reward_rate: U128,
+    /// Timestamp in seconds at which the current emission ends.
+    pub period_end: U64,
+    pub unstake_cooldown: U64,
+}
+
+#[near(serializers = [json])]
+pub struct StakerInfo {
+    pub staked: U128,
+    pub rewards: U128,
+    pub unstaking: U128,
+    pub unstaking_until: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Transfers `amount` tokens from the caller to the reward pool and emits the pool's
+    /// unemitted rewards together with `amount` linearly over `duration` seconds.
+    #[payable]
+    pub fn fund_staking_rewards(&mut self, amount: U128, duration: U64) {
+        assert_one_yocto();
+        self.assert_owner();
+        require!(duration.0 > 0, "Duration must be positive");
+        let owner_id = env::predecessor_account_id();
+        let contract_id = env::current_account_id();
+        self.internal_register_self();
+        self.internal_transfer(
+            &owner_id,
+            &contract_id,
+            amount.0,
+            Some("staking rewards".to_string()),
+        );
+
+        let staking = &mut self.staking;
+        staking.update_reward_per_token();
+        let now = now_seconds();
+        let unemitted = if now < staking.period_end {
+            staking.reward_rate * (staking.period_end - now) as u128
+        } else {
+            0
+        };
+        staking.reward_rate = (unemitted + amount.0) / duration.0 as u128;
+        require!(staking.reward_rate > 0, "Reward rate would be zero");
+        staking.reward_pool += amount.0;
+        staking.last_update = now;
+        staking.period_end = now + duration.0;
+
+        Event::StakingRewardsFunded {
+            amount,
+            reward_rate: staking.reward_rate.into(),
+            period_end: staking.period_end.into(),
+        }
+        .emit();
+    }
+
+    pub fn set_unstake_cooldown(&mut self, unstake_cooldown: U64) {
+        self.assert_owner();
+        self.staking.unstake_cooldown = unstake_cooldown.0;
+    }
+
+    /// Unstakes `amount` tokens of the caller. Without a cooldown they are transferred
+    /// right away, otherwise they can be withdrawn with `withdraw_unstaked` after it.
+    /// Unstaking again before that restarts the cooldown for all unstaking tokens.
+    /// The remaining stake has to be zero or at least `MIN_STAKE`.
+    #[payable]
+    pub fn unstake(&mut self, amount: U128) {
+        assert_one_yocto();
+        require!(amount.0 > 0, "The amount should be a positive number");
+        let account_id = env::predecessor_account_id();
+        let cooldown = self.staking.unstake_cooldown;
+        let staker = self.staking.update_rewards(&account_id);
+        require!(amount.0 <= staker.staked, "Not enough staked tokens");
+        staker.staked -= amount.0;
+        assert_min_stake(staker.staked);
+        if cooldown > 0 {
+            staker.unstaking += amount.0;
+            staker.unstaking_until = env::block_timestamp() + cooldown;
+        }
+        let unstaking_until = staker.unstaking_until;
+        self.staking.total_staked -= amount.0;
+
+        Event::Unstaked {
+            account_id: &account_id,
+            amount,
+            unstaking_until: unstaking_until.into(),
+        }
+        .emit();
+        if cooldown > 0 {
+            self.staking.total_unstaking += amount.0;
+        } else {
+            self.internal_transfer_from_self(&account_id, amount.0, "unstake");
+        }
+        self.internal_remove_empty_staker(&account_id);
+    }
+
+    /// Transfers the caller's unstaked tokens after their cooldown has passed.
+    #[payable]
+    pub fn withdraw_unstaked(&mut self) -> U128 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let staker = self
+            .staking
+            .stakers
+            .get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("Nothing to withdraw"));
+        require!(staker.unstaking > 0, "Nothing to withdraw");
+        require!(
+            env::block_timestamp() >= staker.unstaking_until,
+            "Unstaked tokens are still in cooldown"
+        );
+        let amount = std::mem::take(&mut staker.unstaking);
+        self.staking.total_unstaking -= amount;
+
+        self.internal_transfer_from_self(&account_id, amount, "unstake");
+        self.internal_remove_empty_staker(&account_id);
+        amount.into()
+    }
+
+    #[payable]
+    pub fn claim_rewards(&mut self) -> U128 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let staker = self.staking.update_rewards(&account_id);
+        let amount = std::mem::take(&mut staker.rewards);
+        require!(amount > 0, "Nothing to claim");
+        self.staking.reward_pool -= amount;
+
+        Event::RewardsClaimed {
+            account_id: &account_id,
+            amount: amount.into(),
+        }
+        .emit();
+        self.internal_transfer_from_self(&account_id, amount, "staking rewards");
+        self.internal_remove_empty_staker(&account_id);
+        amount.into()
+    }
+
+    pub fn staking_info(&self) -> StakingInfo {
+        StakingInfo {
+            total_staked: self.staking.total_staked.into(),
+            reward_pool: self.staking.reward_pool.into(),
+            reward_rate: self.staking.reward_rate.into(),
+            period_end: self.staking.period_end.into(),
+            unstake_cooldown: self.staking.unstake_cooldown.into(),
+        }
+    }
+
+    pub fn staker_info(&self, account_id: AccountId) -> Option<StakerInfo> {
+        self.staking
+            .stakers
+            .get(&account_id)
+            .map(|staker| StakerInfo {
+                staked: staker.staked.into(),
+                rewards: earned(staker, self.staking.reward_per_token()).into(),
+                unstaking: staker.unstaking.into(),
+                unstaking_until: staker.unstaking_until.into(),
+            })
+    }
+}
+
+impl Contract {
+    /// Handles `ft_transfer_call` of this token to this contract.
+    /// Stakes have to reach `MIN_STAKE`, otherwise the transfer is refunded.
+    pub(crate) fn internal_on_self_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        amount: u128,
+        msg: &str,
+    ) -> u128 {
+        if msg != STAKE_MSG {
+            return amount;
+        }
+        let staker = self.staking.update_rewards(sender_id);
+        staker.staked += amount;
+        assert_min_stake(staker.staked);
+        self.staking.total_staked += amount;
+        Event::Staked {
+            account_id: sender_id,
+            amount: amount.into(),
+        }
+        .emit();
+        0
+    }
+
+    /// Registers this contract's own account so that it can hold tokens.
+    pub(crate) fn internal_register_self(&mut self) {
+        let contract_id = env::current_account_id();
+        if !self.token.accounts.contains_key(&contract_id) {
+            self.token.internal_register_account(&contract_id);
+        }
+    }
+
+    fn internal_transfer_from_self(&mut self, receiver_id: &AccountId, amount: u128, memo: &str) {
+        let contract_id = env::current_account_id();
+        self.internal_transfer(&contract_id, receiver_id, amount, Some(memo.to_string()));
+    }
+
+    fn internal_remove_empty_staker(&mut self, account_id: &AccountId) {
+        let is_empty = self.staking.stakers.get(account_id).is_some_and(|staker| {
+            staker.staked == 0 && staker.rewards == 0 && staker.unstaking == 0
+        });
+        if is_empty {
+            self.staking.stakers.remove(account_id);
+        }
+    }
+
+    pub(crate) fn staking_reserved(&self) -> u128 {
+        self.staking.reserved()
+    }
+}
+
+fn earned(staker: &Staker, reward_per_token: U256) -> u128 {
+    let earned = U256::from(staker.staked)
+        * (reward_per_token - U256(staker.reward_per_token_paid))
+        / U256::from(REWARD_PRECISION);
+    staker.rewards + earned.as_u128()
+}
+
+fn assert_min_stake(staked: u128) {
+    require!(
+        staked == 0 || staked >= MIN_STAKE,
+        format!("Stake has to be zero or at least {}", MIN_STAKE)
+    );
+}
+
+fn now_seconds() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
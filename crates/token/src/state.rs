@@ -0,0 +1,88 @@
+use crate::{Contract, ContractExt, MigrationSource, StorageKey};
+use near_contract_standards::fungible_token::FungibleToken;
+use near_sdk::{
+    borsh::{self, BorshDeserialize},
+    env, near_bindgen, AccountId, IntoStorageKey,
+};
+
+/// Version of the current state layout.
+///
+/// Released layouts are frozen in this module and must never change.
+/// Bump this version and add a new variant to `VersionedContract` when releasing a new layout.
+pub const CONTRACT_VERSION: u32 = 2;
+
+const STATE_KEY: &[u8] = b"STATE";
+
+/// State layout of version 1, which has been deployed without storing a state version.
+#[derive(BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct ContractV1 {
+    owner: AccountId,
+    migrate_address: AccountId,
+    token: FungibleToken,
+}
+
+pub enum VersionedContract {
+    V1(ContractV1),
+    V2(Contract),
+}
+
+impl VersionedContract {
+    /// Reads the contract state with the layout of the stored state version.
+    pub fn read() -> Self {
+        let state =
+            env::storage_read(STATE_KEY).unwrap_or_else(|| env::panic_str("State is missing"));
+        let version = env::storage_read(&StorageKey::Version.into_storage_key())
+            .map(|version| borsh::from_slice::<u32>(&version).unwrap())
+            .unwrap_or(1);
+        match version {
+            1 => Self::V1(Self::deserialize(&state)),
+            2 => Self::V2(Self::deserialize(&state)),
+            _ => env::panic_str(&format!("Unknown state version {}", version)),
+        }
+    }
+
+    pub fn into_current(self) -> Contract {
+        match self {
+            Self::V1(contract) => {
+                let total_supply = contract.token.total_supply;
+                let mut current =
+                    Contract::init(contract.owner, contract.migrate_address, contract.token);
+                // Version 1 did not track migrations. Old tokens have been migrated 1:1,
+                // so the total supply is a lower bound of what has been migrated and is still held.
+                current.migration_start = 0;
+                current.migration_minted = total_supply;
+                for (_, source) in current.migration_sources.iter_mut() {
+                    *source = MigrationSource {
+                        migrated: total_supply.into(),
+                        minted: total_supply.into(),
+                        balance: total_supply.into(),
+                        ..source.clone()
+                    };
+                }
+                current
+            }
+            Self::V2(contract) => contract,
+        }
+    }
+
+    fn deserialize<T: BorshDeserialize>(state: &[u8]) -> T {
+        borsh::from_slice(state).unwrap_or_else(|_| env::panic_str("Cannot deserialize state"))
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn contract_version(&self) -> u32 {
+        CONTRACT_VERSION
+    }
+}
+
+impl Contract {
+    pub(crate) fn write_state_version() {
+        env::storage_write(
+            &StorageKey::Version.into_storage_key(),
+            &borsh::to_vec(&CONTRACT_VERSION).unwrap(),
+        );
+    }
+}
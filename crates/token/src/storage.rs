@@ -1,9 +1,9 @@
-use crate::{Contract, ContractExt};
+use crate::{batch::MAX_BATCH_SIZE, Contract, ContractExt, PauseFeature};
 use near_contract_standards::{
     fungible_token::events::FtBurn,
     storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement},
 };
-use near_sdk::{near_bindgen, AccountId, NearToken};
+use near_sdk::{env, near_bindgen, require, AccountId, NearToken, Promise, StorageUsage};
 
 #[near_bindgen]
 impl StorageManagement for Contract {
@@ -13,6 +13,7 @@ impl StorageManagement for Contract {
         account_id: Option<AccountId>,
         registration_only: Option<bool>,
     ) -> StorageBalance {
+        self.assert_not_paused(PauseFeature::Storage);
         self.token.storage_deposit(account_id, registration_only)
     }
 
@@ -23,7 +24,21 @@ impl StorageManagement for Contract {
 
     #[payable]
     fn storage_unregister(&mut self, force: Option<bool>) -> bool {
-        if let Some((account_id, balance)) = self.token.internal_storage_unregister(force) {
+        self.assert_not_paused(PauseFeature::Storage);
+        self.assert_not_blocked(&env::predecessor_account_id());
+        require!(
+            self.internal_locked_balance(&env::predecessor_account_id()) == 0,
+            "Can't unregister with locked tokens"
+        );
+        self.locks.remove(&env::predecessor_account_id());
+        self.internal_checkpoint_supply();
+        let unregistered = if self.is_sponsored(&env::predecessor_account_id()) {
+            Some(self.internal_sponsored_storage_unregister(force))
+        } else {
+            self.token.internal_storage_unregister(force)
+        };
+        if let Some((account_id, balance)) = unregistered {
+            self.internal_remove_checkpoints(&account_id);
             if balance > 0 {
                 FtBurn {
                     owner_id: &account_id,
@@ -46,3 +61,71 @@ impl StorageManagement for Contract {
         self.token.storage_balance_of(account_id)
     }
 }
+
+#[near_bindgen]
+impl Contract {
+    /// Registers all unregistered accounts of `account_ids` with the minimum storage balance.
+    /// The attached deposit needs to cover all of them and the rest is refunded.
+    /// Returns the accounts that have been newly registered.
+    #[payable]
+    pub fn storage_deposit_batch(&mut self, account_ids: Vec<AccountId>) -> Vec<AccountId> {
+        self.assert_not_paused(PauseFeature::Storage);
+        require!(
+            account_ids.len() <= MAX_BATCH_SIZE,
+            format!("At most {} accounts per batch", MAX_BATCH_SIZE)
+        );
+        let min_balance = self.token.storage_balance_bounds().min;
+
+        let mut registered = vec![];
+        for account_id in account_ids {
+            if !self.token.accounts.contains_key(&account_id) {
+                self.token.internal_register_account(&account_id);
+                registered.push(account_id);
+            }
+        }
+
+        let cost = min_balance.saturating_mul(registered.len() as u128);
+        let refund = env::attached_deposit()
+            .checked_sub(cost)
+            .unwrap_or_else(|| {
+                env::panic_str(&format!(
+                    "The attached deposit is less than the minimum storage balance of {}",
+                    cost
+                ))
+            });
+        if !refund.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        registered
+    }
+}
+
+impl Contract {
+    /// Charges `account_id` for storage used since `initial_storage_usage` from the attached deposit,
+    /// or refunds released storage. Any remaining attached deposit is refunded.
+    pub(crate) fn internal_settle_storage(
+        &self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        let storage_usage = env::storage_usage();
+        let attached_deposit = env::attached_deposit();
+        let refund = if storage_usage >= initial_storage_usage {
+            let cost = env::storage_byte_cost()
+                .saturating_mul((storage_usage - initial_storage_usage).into());
+            attached_deposit.checked_sub(cost).unwrap_or_else(|| {
+                env::panic_str(&format!(
+                    "The attached deposit is less than the storage cost of {}",
+                    cost
+                ))
+            })
+        } else {
+            env::storage_byte_cost()
+                .saturating_mul((initial_storage_usage - storage_usage).into())
+                .saturating_add(attached_deposit)
+        };
+        if !refund.is_zero() {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+    }
+}
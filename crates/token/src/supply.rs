@@ -0,0 +1,90 @@
+use crate::{Contract, ContractExt, PauseFeature, Role};
+use near_contract_standards::fungible_token::events::{FtBurn, FtMint};
+use near_sdk::{assert_one_yocto, env, json_types::U128, near_bindgen, require, AccountId};
+
+#[near_bindgen]
+impl Contract {
+    /// Mints `amount` tokens to `account_id`, registering the account if necessary.
+    pub fn mint(&mut self, account_id: AccountId, amount: U128, memo: Option<String>) {
+        self.assert_role(Role::Minter);
+        self.assert_not_paused(PauseFeature::Minting);
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(
+            amount.0 <= self.mintable_amount(),
+            "Minting would exceed max supply"
+        );
+        self.internal_mint(&account_id, amount.0, memo.as_deref());
+    }
+
+    /// Burns `amount` tokens of the caller.
+    #[payable]
+    pub fn ft_burn(&mut self, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        self.assert_not_paused(PauseFeature::Transfers);
+        require!(amount.0 > 0, "The amount should be a positive number");
+        let account_id = env::predecessor_account_id();
+        self.assert_unlocked(&account_id, amount.0);
+        self.internal_checkpoint(&account_id);
+        self.internal_checkpoint_supply();
+        self.token.internal_withdraw(&account_id, amount.0);
+        FtBurn {
+            owner_id: &account_id,
+            amount,
+            memo: memo.as_deref(),
+        }
+        .emit();
+    }
+
+    /// Sets the maximum total supply. It can't be lower than the current total supply,
+    /// including tokens of pending redemptions that might be minted again.
+    pub fn set_max_supply(&mut self, max_supply: Option<U128>) {
+        self.assert_owner();
+        if let Some(max_supply) = max_supply {
+            require!(
+                max_supply.0 >= self.reserved_supply(),
+                "Max supply can't be lower than total supply"
+            );
+        }
+        self.max_supply = max_supply.map(|max_supply| max_supply.0);
+    }
+
+    pub fn max_supply(&self) -> Option<U128> {
+        self.max_supply.map(Into::into)
+    }
+}
+
+impl Contract {
+    /// Amount of tokens that can still be minted before reaching max supply.
+    pub(crate) fn mintable_amount(&self) -> u128 {
+        self.max_supply.map_or(u128::MAX, |max_supply| {
+            max_supply.saturating_sub(self.reserved_supply())
+        })
+    }
+
+    /// Total supply plus the tokens that failed redemptions would mint again.
+    fn reserved_supply(&self) -> u128 {
+        self.token.total_supply + self.redeem_pending
+    }
+
+    /// Mints tokens without checking max supply and registers the account if necessary.
+    /// Refunds of failed redemptions rely on this, because their amount has been reserved.
+    pub(crate) fn internal_mint(
+        &mut self,
+        account_id: &AccountId,
+        amount: u128,
+        memo: Option<&str>,
+    ) {
+        if !self.token.accounts.contains_key(account_id) {
+            self.token.internal_register_account(account_id);
+        }
+        self.internal_checkpoint(account_id);
+        self.internal_checkpoint_supply();
+        self.token.internal_deposit(account_id, amount);
+        FtMint {
+            owner_id: account_id,
+            amount: amount.into(),
+            memo,
+        }
+        .emit();
+    }
+}
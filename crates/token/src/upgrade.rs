@@ -0,0 +1,187 @@
+use crate::{event::Event, Contract, ContractExt, Role, StorageKey};
+use near_sdk::{
+    env,
+    json_types::{Base58CryptoHash, U64},
+    near, near_bindgen, require, CryptoHash, Gas, IntoStorageKey, Promise,
+};
+
+const GAS_FOR_RESOLVE_UPGRADE: Gas = Gas::from_tgas(50);
+
+/// One day in nanoseconds.
+pub const DEFAULT_UPGRADE_TIMELOCK: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct StagedCode {
+    /// SHA-256 hash of the staged code.
+    pub hash: CryptoHash,
+    /// Timestamp in nanoseconds after which the staged code can be deployed.
+    pub eligible_at: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Stages the code passed as raw input for deployment after the upgrade timelock.
+    /// Staging new code replaces previously staged code and restarts the timelock.
+    pub fn stage_code(&mut self) -> Base58CryptoHash {
+        self.assert_role(Role::Upgrader);
+
+        let code = env::input().expect("Error: No input");
+        let staged_code = StagedCode {
+            hash: env::sha256_array(&code),
+            eligible_at: env::block_timestamp() + self.upgrade_timelock,
+        };
+        env::storage_write(&StorageKey::StagedCode.into_storage_key(), &code);
+
+        Event::CodeStaged {
+            code_hash: staged_code.hash.into(),
+            eligible_at: staged_code.eligible_at.into(),
+        }
+        .emit();
+        let code_hash = staged_code.hash.into();
+        self.staged_code = Some(staged_code);
+        code_hash
+    }
+
+    pub fn cancel_staged_code(&mut self) {
+        self.assert_role(Role::Upgrader);
+        let staged_code = self
+            .staged_code
+            .take()
+            .unwrap_or_else(|| env::panic_str("No code staged"));
+        env::storage_remove(&StorageKey::StagedCode.into_storage_key());
+
+        Event::StagedCodeCancelled {
+            code_hash: staged_code.hash.into(),
+        }
+        .emit();
+    }
+
+    /// Stores the code passed as raw input as the currently deployed code, which is needed
+    /// to roll back the first staged upgrade. It can only be registered by the owner while the
+    /// deployed code is unknown, and `deployed_code_hash` should be checked against the
+    /// account's code hash.
+    pub fn register_deployed_code(&mut self) -> Base58CryptoHash {
+        self.assert_owner();
+        let key = StorageKey::DeployedCode.into_storage_key();
+        require!(
+            !env::storage_has_key(&key),
+            "Deployed code is already known"
+        );
+        let code = env::input().expect("Error: No input");
+        env::storage_write(&key, &code);
+        let code_hash = env::sha256_array(&code).into();
+        Event::DeployedCodeRegistered { code_hash }.emit();
+        code_hash
+    }
+
+    /// Deploys the staged code once its timelock has elapsed and migrates the state afterwards.
+    ///
+    /// The previously deployed code is retained, so that it can be redeployed
+    /// if migrating the state fails with the new code. Deploying therefore requires
+    /// the deployed code to be known, see `register_deployed_code`.
+    pub fn deploy_staged(&mut self) -> Promise {
+        self.assert_role(Role::Upgrader);
+        let deployed_key = StorageKey::DeployedCode.into_storage_key();
+        let previous_code = env::storage_read(&deployed_key)
+            .unwrap_or_else(|| env::panic_str("Deployed code is unknown and can't be rolled back"));
+        let staged_code = self
+            .staged_code
+            .take()
+            .unwrap_or_else(|| env::panic_str("No code staged"));
+        require!(
+            env::block_timestamp() >= staged_code.eligible_at,
+            "Staged code is still timelocked"
+        );
+        let key = StorageKey::StagedCode.into_storage_key();
+        let code = env::storage_read(&key).unwrap_or_else(|| env::panic_str("No code staged"));
+        env::storage_remove(&key);
+
+        env::storage_write(&StorageKey::RollbackCode.into_storage_key(), &previous_code);
+        env::storage_write(&deployed_key, &code);
+
+        Event::StagedCodeDeployed {
+            code_hash: staged_code.hash.into(),
+        }
+        .emit();
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .then(Self::ext(env::current_account_id()).migrate())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_UPGRADE)
+                    .on_upgrade_migrated(staged_code.hash.into()),
+            )
+            .as_return()
+    }
+
+    /// Redeploys the previous code if migrating the state failed.
+    ///
+    /// This callback already runs with the new code, but must not read the state,
+    /// because it still has the previous layout if migrating failed.
+    /// The rollback therefore only works if the new code has a working version of this
+    /// callback. Code that can't execute it stays deployed with the unmigrated state,
+    /// so new code should be tested against a copy of the state before it is staged.
+    #[private]
+    pub fn on_upgrade_migrated(code_hash: Base58CryptoHash) -> bool {
+        let rollback_key = StorageKey::RollbackCode.into_storage_key();
+        if near_sdk::is_promise_success() {
+            env::storage_remove(&rollback_key);
+            Event::UpgradeSucceeded { code_hash }.emit();
+            return true;
+        }
+
+        let previous_code = env::storage_read(&rollback_key);
+        Event::UpgradeFailed {
+            code_hash,
+            rollback_code_hash: previous_code
+                .as_ref()
+                .map(|code| env::sha256_array(code).into()),
+        }
+        .emit();
+        if let Some(previous_code) = previous_code {
+            env::storage_remove(&rollback_key);
+            env::storage_write(&StorageKey::DeployedCode.into_storage_key(), &previous_code);
+            Promise::new(env::current_account_id()).deploy_contract(previous_code);
+        }
+        false
+    }
+
+    /// Sets the duration in nanoseconds that staged code has to wait before it can be deployed.
+    /// Code that is already staged keeps its timelock.
+    ///
+    /// The timelock can only be increased, because lowering it would allow staging and
+    /// deploying code right away. It can still be lowered by a timelocked upgrade.
+    pub fn set_upgrade_timelock(&mut self, duration: U64) {
+        self.assert_owner();
+        require!(
+            duration.0 >= self.upgrade_timelock,
+            "Upgrade timelock can't be lowered"
+        );
+        self.upgrade_timelock = duration.0;
+    }
+
+    pub fn upgrade_timelock(&self) -> U64 {
+        self.upgrade_timelock.into()
+    }
+
+    pub fn staged_code_hash(&self) -> Option<Base58CryptoHash> {
+        self.staged_code
+            .as_ref()
+            .map(|staged_code| staged_code.hash.into())
+    }
+
+    /// Hash of the code deployed with the last staged upgrade or registered as deployed.
+    pub fn deployed_code_hash(&self) -> Option<Base58CryptoHash> {
+        env::storage_read(&StorageKey::DeployedCode.into_storage_key())
+            .map(|code| env::sha256_array(&code).into())
+    }
+
+    /// Timestamp in nanoseconds after which the staged code can be deployed.
+    pub fn staged_code_eligible_at(&self) -> Option<U64> {
+        self.staged_code
+            .as_ref()
+            .map(|staged_code| staged_code.eligible_at.into())
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{event::Event, Contract, ContractExt, PauseFeature};
+use near_sdk::{
+    env,
+    json_types::{U128, U64},
+    near, near_bindgen, require,
+    store::IterableMap,
+    AccountId, IntoStorageKey,
+};
+
+/// Tokens that are held by this contract and released linearly to `beneficiary_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct VestingSchedule {
+    pub beneficiary_id: AccountId,
+    pub total: U128,
+    pub claimed: U128,
+    /// Timestamp in nanoseconds at which vesting starts.
+    pub start: U64,
+    /// Duration in nanoseconds after `start` before which nothing is vested.
+    pub cliff: U64,
+    /// Duration in nanoseconds after `start` at which everything is vested.
+    pub duration: U64,
+    /// Whether the owner can revoke tokens that have not vested yet.
+    pub revocable: bool,
+}
+
+impl VestingSchedule {
+    pub fn vested_at(&self, timestamp: u64) -> u128 {
+        let total = self.total.0;
+        if timestamp < self.start.0.saturating_add(self.cliff.0) {
+            return 0;
+        }
+        let elapsed = (timestamp - self.start.0) as u128;
+        let duration = self.duration.0 as u128;
+        if elapsed >= duration {
+            return total;
+        }
+        // Split to avoid overflowing `total * elapsed`.
+        total / duration * elapsed + total % duration * elapsed / duration
+    }
+
+    pub fn claimable(&self) -> u128 {
+        self.vested_at(env::block_timestamp()) - self.claimed.0
+    }
+}
+
+#[near(serializers = [borsh])]
+pub struct Vesting {
+    next_schedule_id: u64,
+    schedules: IterableMap<u64, VestingSchedule>,
+    /// Amount of tokens held by this contract that have not been claimed yet.
+    reserved: u128,
+}
+
+impl Vesting {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self {
+            next_schedule_id: 0,
+            schedules: IterableMap::new(prefix),
+            reserved: 0,
+        }
+    }
+}
+
+/// Parameters of a new vesting schedule.
+#[near(serializers = [json])]
+pub struct VestingParams {
+    pub beneficiary_id: AccountId,
+    pub total: U128,
+    /// Timestamp in nanoseconds at which vesting starts. Defaults to the current block timestamp.
+    pub start: Option<U64>,
+    /// Duration in nanoseconds after `start` before which nothing is vested.
+    pub cliff: U64,
+    /// Duration in nanoseconds after `start` at which everything is vested.
+    pub duration: U64,
+    /// Whether the owner can revoke tokens that have not vested yet.
+    pub revocable: bool,
+}
+
+#[near(serializers = [json])]
+pub struct VestingBalance {
+    /// Vested tokens that can be claimed now.
+    pub claimable: U128,
+    /// Tokens that have not vested yet.
+    pub locked: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Creates a vesting schedule for `total` tokens, which are either minted or transferred
+    /// from the caller to this contract.
+    pub fn create_vesting(&mut self, params: VestingParams, mint: bool) -> U64 {
+        let VestingParams {
+            beneficiary_id,
+            total,
+            start,
+            cliff,
+            duration,
+            revocable,
+        } = params;
+        self.assert_owner();
+        require!(total.0 > 0, "Total must be positive");
+        require!(duration.0 > 0, "Duration must be positive");
+        require!(cliff.0 <= duration.0, "Cliff can't exceed duration");
+        self.assert_not_blocked(&beneficiary_id);
+
+        let contract_id = env::current_account_id();
+        if mint {
+            self.assert_not_paused(PauseFeature::Minting);
+            require!(total.0 <= self.mintable_amount(), "Exceeds max supply");
+            self.internal_mint(&contract_id, total.0, Some("vesting"));
+        } else {
+            self.internal_register_self();
+            let sender_id = env::predecessor_account_id();
+            self.internal_transfer(
+                &sender_id,
+                &contract_id,
+                total.0,
+                Some("vesting".to_string()),
+            );
+        }
+
+        let schedule_id = self.vesting.next_schedule_id;
+        self.vesting.next_schedule_id += 1;
+        self.vesting.reserved += total.0;
+        self.vesting.schedules.insert(
+            schedule_id,
+            VestingSchedule {
+                beneficiary_id: beneficiary_id.clone(),
+                total,
+                claimed: U128(0),
+                start: start.unwrap_or(env::block_timestamp().into()),
+                cliff,
+                duration,
+                revocable,
+            },
+        );
+        self.vesting_schedule_ids
+            .entry(beneficiary_id.clone())
+            .or_default()
+            .push(schedule_id);
+        Event::VestingCreated {
+            schedule_id: schedule_id.into(),
+            beneficiary_id: &beneficiary_id,
+            total,
+        }
+        .emit();
+        schedule_id.into()
+    }
+
+    /// Stops a revocable schedule. Tokens that have vested so far stay claimable by the
+    /// beneficiary and the rest becomes self held balance of this contract.
+    pub fn revoke_vesting(&mut self, schedule_id: U64) -> U128 {
+        self.assert_owner();
+        let schedule = self
+            .vesting
+            .schedules
+            .get_mut(&schedule_id.0)
+            .unwrap_or_else(|| env::panic_str("Vesting schedule not found"));
+        require!(schedule.revocable, "Vesting schedule is not revocable");
+
+        let vested = schedule.vested_at(env::block_timestamp());
+        let unvested = schedule.total.0 - vested;
+        schedule.total = vested.into();
+        schedule.cliff = U64(0);
+        schedule.duration = U64(0);
+        schedule.revocable = false;
+        let fully_claimed = schedule.claimed == schedule.total;
+        let beneficiary_id = schedule.beneficiary_id.clone();
+        if fully_claimed {
+            self.vesting.schedules.remove(&schedule_id.0);
+            let schedule_ids = self.internal_vesting_schedule_ids(&beneficiary_id);
+            self.internal_set_vesting_schedule_ids(
+                &beneficiary_id,
+                schedule_ids
+                    .into_iter()
+                    .filter(|id| *id != schedule_id.0)
+                    .collect(),
+            );
+        }
+        self.vesting.reserved -= unvested;
+
+        Event::VestingRevoked {
+            schedule_id,
+            unvested: unvested.into(),
+        }
+        .emit();
+        unvested.into()
+    }
+
+    /// Transfers all vested tokens of the caller's schedules to the caller.
+    /// Schedules are removed once they have been fully claimed.
+    pub fn claim_vested(&mut self) -> U128 {
+        let beneficiary_id = env::predecessor_account_id();
+        let mut amount = 0;
+        let mut remaining = vec![];
+        for schedule_id in self.internal_vesting_schedule_ids(&beneficiary_id) {
+            let schedule = self.vesting.schedules.get_mut(&schedule_id).unwrap();
+            let claimable = schedule.claimable();
+            amount += claimable;
+            schedule.claimed.0 += claimable;
+            if schedule.claimed == schedule.total {
+                self.vesting.schedules.remove(&schedule_id);
+            } else {
+                remaining.push(schedule_id);
+            }
+        }
+        self.internal_set_vesting_schedule_ids(&beneficiary_id, remaining);
+        require!(amount > 0, "Nothing to claim");
+
+        self.vesting.reserved -= amount;
+        let contract_id = env::current_account_id();
+        self.internal_transfer(
+            &contract_id,
+            &beneficiary_id,
+            amount,
+            Some("vesting claim".to_string()),
+        );
+        amount.into()
+    }
+
+    pub fn vesting_schedule(&self, schedule_id: U64) -> Option<VestingSchedule> {
+        self.vesting.schedules.get(&schedule_id.0).cloned()
+    }
+
+    pub fn vesting_schedules_of(&self, account_id: AccountId) -> Vec<(U64, VestingSchedule)> {
+        self.internal_vesting_schedules_of(&account_id)
+            .map(|(schedule_id, schedule)| (schedule_id.into(), schedule.clone()))
+            .collect()
+    }
+
+    pub fn vesting_balance_of(&self, account_id: AccountId) -> VestingBalance {
+        let now = env::block_timestamp();
+        let (claimable, locked) = self.internal_vesting_schedules_of(&account_id).fold(
+            (0, 0),
+            |(claimable, locked), (_, schedule)| {
+                let vested = schedule.vested_at(now);
+                (
+                    claimable + vested - schedule.claimed.0,
+                    locked + schedule.total.0 - vested,
+                )
+            },
+        );
+        VestingBalance {
+            claimable: claimable.into(),
+            locked: locked.into(),
+        }
+    }
+}
+
+impl Contract {
+    /// Tokens held by this contract that are reserved for vesting beneficiaries.
+    pub(crate) fn vesting_reserved(&self) -> u128 {
+        self.vesting.reserved
+    }
+
+    fn internal_vesting_schedule_ids(&self, account_id: &AccountId) -> Vec<u64> {
+        self.vesting_schedule_ids
+            .get(account_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn internal_set_vesting_schedule_ids(
+        &mut self,
+        account_id: &AccountId,
+        schedule_ids: Vec<u64>,
+    ) {
+        if schedule_ids.is_empty() {
+            self.vesting_schedule_ids.remove(account_id);
+        } else {
+            self.vesting_schedule_ids
+                .insert(account_id.clone(), schedule_ids);
+        }
+    }
+
+    fn internal_vesting_schedules_of<'a>(
+        &'a self,
+        account_id: &AccountId,
+    ) -> impl Iterator<Item = (u64, &'a VestingSchedule)> {
+        self.internal_vesting_schedule_ids(account_id)
+            .into_iter()
+            .map(|schedule_id| (schedule_id, &self.vesting.schedules[&schedule_id]))
+    }
+}
@@ -1,8 +1,13 @@
 mod util;
 
-use near_sdk::json_types::U128;
+use near_sdk::{
+    json_types::{Base64VecU8, U128, U64},
+    serde_json::json,
+    NearToken,
+};
 
 pub use crate::util::*;
+use tokio::fs;
 
 #[tokio::test]
 async fn test_token_migration() -> anyhow::Result<()> {
@@ -35,15 +40,1152 @@ async fn test_token_migration() -> anyhow::Result<()> {
     assert_eq!(balance, U128(1_000_000));
     let balance = view::ft_balance_of(jlu.as_account(), jlu_old.id()).await?;
     assert_eq!(balance, U128(1_000_000));
+    let migrated = view::migrated_amount_of(&owner, jlu.id()).await?;
+    assert_eq!(migrated, U128(1_000_000));
 
     assert_ft_mint_events(
         &events,
         vec![FtMint {
             owner_id: owner.id().clone(),
             amount: U128::from(1_000_000),
+            memo: Some("migration ratio 1:1".to_string()),
+        }],
+    )?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_migration_closed() -> anyhow::Result<()> {
+    let Init {
+        owner,
+        jlu_old,
+        jlu,
+        ..
+    } = initialize_contracts().await?;
+
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_000).await?;
+
+    call::close_migration(&owner, jlu.id()).await?;
+
+    let (_, events) = call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_000),
+        "".to_string(),
+    )
+    .await?;
+
+    let balance = view::ft_balance_of(&owner, jlu_old.id()).await?;
+    assert_eq!(balance, U128(1_000_000));
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(0));
+
+    assert_ft_mint_events(&events, vec![])?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_migration_paused() -> anyhow::Result<()> {
+    let Init {
+        owner,
+        jlu_old,
+        jlu,
+        ..
+    } = initialize_contracts().await?;
+
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_000).await?;
+
+    call::pause(&owner, jlu.id(), "migration").await?;
+
+    call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_000),
+        "".to_string(),
+    )
+    .await?;
+
+    let balance = view::ft_balance_of(&owner, jlu_old.id()).await?;
+    assert_eq!(balance, U128(1_000_000));
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_migration_ratio() -> anyhow::Result<()> {
+    let Init {
+        owner,
+        jlu_old,
+        jlu,
+        ..
+    } = initialize_contracts().await?;
+
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_500).await?;
+
+    call::set_migration_ratio(&owner, jlu.id(), jlu_old.id(), U128(1), U128(1_000)).await?;
+
+    let (_, events) = call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_500),
+        "".to_string(),
+    )
+    .await?;
+
+    let balance = view::ft_balance_of(&owner, jlu_old.id()).await?;
+    assert_eq!(balance, U128(500));
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(1_000));
+    let balance = view::ft_balance_of(jlu.as_account(), jlu_old.id()).await?;
+    assert_eq!(balance, U128(1_000_000));
+
+    assert_ft_mint_events(
+        &events,
+        vec![FtMint {
+            owner_id: owner.id().clone(),
+            amount: U128::from(1_000),
+            memo: Some("migration ratio 1:1000".to_string()),
+        }],
+    )?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_redeem() -> anyhow::Result<()> {
+    let Init {
+        owner,
+        jlu_old,
+        jlu,
+        ..
+    } = initialize_contracts().await?;
+
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_000).await?;
+
+    call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_000),
+        "".to_string(),
+    )
+    .await?;
+
+    call::redeem(&owner, jlu.id(), jlu_old.id(), U128(400_000)).await?;
+
+    let balance = view::ft_balance_of(&owner, jlu_old.id()).await?;
+    assert_eq!(balance, U128(400_000));
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(600_000));
+    let balance = view::ft_balance_of(jlu.as_account(), jlu_old.id()).await?;
+    assert_eq!(balance, U128(600_000));
+    let total_supply = view::ft_total_supply(&jlu).await?;
+    assert_eq!(total_supply, U128(600_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_redeem_refund() -> anyhow::Result<()> {
+    let Init {
+        worker,
+        owner,
+        jlu_old,
+        jlu,
+    } = initialize_contracts().await?;
+
+    let user = worker.dev_create_account().await?;
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+    call::storage_deposit(jlu.id(), &user, None, None).await?;
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_000).await?;
+    call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_000),
+        "".to_string(),
+    )
+    .await?;
+    call::ft_transfer(&owner, jlu.id(), user.id(), U128(400_000)).await?;
+    call::set_max_supply(&owner, jlu.id(), Some(U128(1_000_000))).await?;
+
+    // The user isn't registered with the source token, so the transfer fails and the
+    // burned tokens are minted again within max supply.
+    call::redeem(&user, jlu.id(), jlu_old.id(), U128(400_000)).await?;
+
+    let balance = view::ft_balance_of(&user, jlu.id()).await?;
+    assert_eq!(balance, U128(400_000));
+    let total_supply = view::ft_total_supply(&jlu).await?;
+    assert_eq!(total_supply, U128(1_000_000));
+    let balance = view::ft_balance_of(jlu.as_account(), jlu_old.id()).await?;
+    assert_eq!(balance, U128(1_000_000));
+    assert!(call::mint(&owner, jlu.id(), owner.id(), U128(1))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_state_migration_from_v1() -> anyhow::Result<()> {
+    let Init {
+        worker,
+        owner,
+        jlu_old,
+        ..
+    } = initialize_contracts().await?;
+
+    let jlu = worker
+        .dev_deploy(&fs::read("../../res/token_v1.wasm").await?)
+        .await?;
+    jlu.call("new")
+        .args_json((owner.id(), jlu_old.id()))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    call::storage_deposit(jlu_old.id(), jlu.as_account(), None, None).await?;
+    call::mint_tokens(&jlu_old, owner.id(), 1_000_000).await?;
+    call::ft_transfer_call(
+        &owner,
+        jlu_old.id(),
+        jlu.id(),
+        U128::from(1_000_000),
+        "".to_string(),
+    )
+    .await?;
+
+    call::upgrade_v1(&owner, jlu.id(), fs::read("../../res/token.wasm").await?).await?;
+
+    assert_eq!(view::contract_version(&jlu).await?, token::CONTRACT_VERSION);
+    assert_eq!(view::owner(&jlu).await?, *owner.id());
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(1_000_000));
+    let stats = view::migration_stats(&jlu).await?;
+    assert_eq!(stats.total_minted, U128(1_000_000));
+    let source = &stats.sources[jlu_old.id()];
+    assert_eq!(source.migrated, U128(1_000_000));
+    assert_eq!(source.balance, U128(1_000_000));
+
+    // The migrated state keeps working with the new code.
+    call::redeem(&owner, jlu.id(), jlu_old.id(), U128(400_000)).await?;
+    let balance = view::ft_balance_of(&owner, jlu_old.id()).await?;
+    assert_eq!(balance, U128(400_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_and_burn() -> anyhow::Result<()> {
+    let Init { owner, jlu, .. } = initialize_contracts().await?;
+
+    let (_, events) = call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    assert_ft_mint_events(
+        &events,
+        vec![FtMint {
+            owner_id: owner.id().clone(),
+            amount: U128::from(1_000),
             memo: None,
         }],
     )?;
 
+    call::ft_burn(&owner, jlu.id(), U128(400)).await?;
+
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(600));
+    let total_supply = view::ft_total_supply(&jlu).await?;
+    assert_eq!(total_supply, U128(600));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ownership_transfer() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let new_owner = worker.dev_create_account().await?;
+
+    call::propose_owner(&owner, jlu.id(), new_owner.id()).await?;
+    assert_eq!(view::owner(&jlu).await?, *owner.id());
+
+    call::accept_owner(&new_owner, jlu.id()).await?;
+    assert_eq!(view::owner(&jlu).await?, *new_owner.id());
+    assert_eq!(
+        view::role_members(&jlu, "minter").await?,
+        vec![new_owner.id().clone()]
+    );
+    assert!(call::mint(&owner, jlu.id(), owner.id(), U128(1))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_roles() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let minter = worker.dev_create_account().await?;
+    assert!(call::mint(&minter, jlu.id(), minter.id(), U128(100))
+        .await
+        .is_err());
+    assert!(call::grant_role(&minter, jlu.id(), "minter", minter.id())
+        .await
+        .is_err());
+
+    call::grant_role(&owner, jlu.id(), "minter", minter.id()).await?;
+    let mut members = view::role_members(&jlu, "minter").await?;
+    members.sort();
+    let mut expected = vec![owner.id().clone(), minter.id().clone()];
+    expected.sort();
+    assert_eq!(members, expected);
+    call::mint(&minter, jlu.id(), minter.id(), U128(100)).await?;
+    assert_eq!(view::ft_balance_of(&minter, jlu.id()).await?, U128(100));
+
+    call::revoke_role(&owner, jlu.id(), "minter", minter.id()).await?;
+    assert_eq!(
+        view::role_members(&jlu, "minter").await?,
+        vec![owner.id().clone()]
+    );
+    assert!(call::mint(&minter, jlu.id(), minter.id(), U128(100))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staged_code() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let user = worker.dev_create_account().await?;
+    let code = fs::read("../../res/token.wasm").await?;
+    assert!(call::stage_code(&user, jlu.id(), code.clone())
+        .await
+        .is_err());
+
+    let code_hash = call::stage_code(&owner, jlu.id(), code).await?;
+    assert_eq!(view::staged_code_hash(&jlu).await?, Some(code_hash));
+    assert!(call::deploy_staged(&owner, jlu.id()).await.is_err());
+
+    call::cancel_staged_code(&owner, jlu.id()).await?;
+    assert_eq!(view::staged_code_hash(&jlu).await?, None);
+    assert!(call::deploy_staged(&owner, jlu.id()).await.is_err());
+    assert!(call::cancel_staged_code(&owner, jlu.id()).await.is_err());
+
+    let timelock = view::upgrade_timelock(&jlu).await?;
+    assert!(call::set_upgrade_timelock(&owner, jlu.id(), U64(0))
+        .await
+        .is_err());
+    call::set_upgrade_timelock(&owner, jlu.id(), U64(timelock.0 * 2)).await?;
+    assert_eq!(view::upgrade_timelock(&jlu).await?, U64(timelock.0 * 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staged_upgrade_and_rollback() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+    let code = fs::read("../../res/token.wasm").await?;
+
+    call::stage_code(&owner, jlu.id(), code.clone()).await?;
+    let eligible_at = view::staged_code_eligible_at(&jlu).await?.unwrap();
+    wait_until(&worker, eligible_at.0).await?;
+    // The deployed code has to be known before anything can be deployed.
+    assert!(call::deploy_staged(&owner, jlu.id()).await.is_err());
+
+    let code_hash = call::register_deployed_code(&owner, jlu.id(), code.clone()).await?;
+    assert_eq!(view::deployed_code_hash(&jlu).await?, Some(code_hash));
+    assert!(call::register_deployed_code(&owner, jlu.id(), code.clone())
+        .await
+        .is_err());
+
+    assert!(call::deploy_staged(&owner, jlu.id()).await?);
+    assert_eq!(view::staged_code_hash(&jlu).await?, None);
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(1_000));
+
+    // Migrating fails with an unknown state version, so the previous code is redeployed.
+    call::stage_code(&owner, jlu.id(), code).await?;
+    let eligible_at = view::staged_code_eligible_at(&jlu).await?.unwrap();
+    wait_until(&worker, eligible_at.0).await?;
+    worker
+        .patch_state(
+            jlu.id(),
+            &near_sdk::borsh::to_vec(&token::StorageKey::Version)?,
+            &near_sdk::borsh::to_vec(&99u32)?,
+        )
+        .await?;
+    assert!(!call::deploy_staged(&owner, jlu.id()).await?);
+    assert_eq!(view::deployed_code_hash(&jlu).await?, Some(code_hash));
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(1_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multisig_mint() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let member = worker.dev_create_account().await?;
+
+    call::set_multisig(&owner, jlu.id(), vec![owner.id(), member.id()], 2).await?;
+
+    let proposal_id = call::multisig_propose(
+        &owner,
+        jlu.id(),
+        json!({ "mint": { "account_id": member.id(), "amount": "1000", "memo": null } }),
+    )
+    .await?;
+
+    assert!(call::multisig_execute(&owner, jlu.id(), proposal_id)
+        .await
+        .is_err());
+
+    call::multisig_confirm(&member, jlu.id(), proposal_id).await?;
+
+    // A failed action keeps the proposal, so that it can be executed again.
+    call::set_max_supply(&owner, jlu.id(), Some(U128(500))).await?;
+    let (res, _) = call::multisig_execute(&member, jlu.id(), proposal_id).await?;
+    assert!(!res.json::<bool>()?);
+    let proposal = view::multisig_proposal(&jlu, proposal_id).await?.unwrap();
+    assert!(!proposal.executing);
+    call::set_max_supply(&owner, jlu.id(), None).await?;
+
+    let (res, events) = call::multisig_execute(&member, jlu.id(), proposal_id).await?;
+    assert!(res.json::<bool>()?);
+    assert!(view::multisig_proposal(&jlu, proposal_id).await?.is_none());
+
+    assert_ft_mint_events(
+        &events,
+        vec![FtMint {
+            owner_id: member.id().clone(),
+            amount: U128::from(1_000),
+            memo: None,
+        }],
+    )?;
+    let balance = view::ft_balance_of(&member, jlu.id()).await?;
+    assert_eq!(balance, U128(1_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multisig_ownership() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let member = worker.dev_create_account().await?;
+    call::set_multisig(&owner, jlu.id(), vec![member.id()], 1).await?;
+    call::propose_owner(&owner, jlu.id(), jlu.id()).await?;
+
+    let proposal_id = call::multisig_propose(&member, jlu.id(), "accept_owner").await?;
+    call::multisig_execute(&member, jlu.id(), proposal_id).await?;
+    assert_eq!(view::owner(&jlu).await?, *jlu.id());
+    assert_eq!(
+        view::role_members(&jlu, "minter").await?,
+        vec![jlu.id().clone()]
+    );
+
+    // The previous owner has no privileges left.
+    assert!(call::mint(&owner, jlu.id(), owner.id(), U128(1))
+        .await
+        .is_err());
+    assert!(call::set_multisig(&owner, jlu.id(), vec![owner.id()], 1)
+        .await
+        .is_err());
+
+    let proposal_id = call::multisig_propose(
+        &member,
+        jlu.id(),
+        json!({ "mint": { "account_id": member.id(), "amount": "1000", "memo": null } }),
+    )
+    .await?;
+    call::multisig_execute(&member, jlu.id(), proposal_id).await?;
+    assert_eq!(view::ft_balance_of(&member, jlu.id()).await?, U128(1_000));
+
+    let args = json!({ "max_supply": "1000" }).to_string().into_bytes();
+    let proposal_id = call::multisig_propose(
+        &member,
+        jlu.id(),
+        json!({ "call": { "method_name": "set_max_supply", "args": Base64VecU8(args) } }),
+    )
+    .await?;
+    call::multisig_execute(&member, jlu.id(), proposal_id).await?;
+    assert_eq!(view::max_supply(&jlu).await?, Some(U128(1_000)));
+
+    assert!(call::multisig_propose(
+        &member,
+        jlu.id(),
+        json!({ "call": { "method_name": "on_redeem", "args": Base64VecU8(vec![]) } }),
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blocked_account() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let user = worker.dev_create_account().await?;
+
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+    call::mint(&owner, jlu.id(), user.id(), U128(1_000)).await?;
+
+    call::block_account(&owner, jlu.id(), user.id()).await?;
+
+    assert!(call::ft_transfer(&owner, jlu.id(), user.id(), U128(100))
+        .await
+        .is_err());
+    assert!(call::ft_transfer(&user, jlu.id(), owner.id(), U128(100))
+        .await
+        .is_err());
+
+    let balance = view::ft_balance_of(&user, jlu.id()).await?;
+    assert_eq!(balance, U128(1_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_return_self_held_tokens() -> anyhow::Result<()> {
+    let Init { owner, jlu, .. } = initialize_contracts().await?;
+
+    call::storage_deposit(jlu.id(), &owner, Some(jlu.id()), None).await?;
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+    call::ft_transfer(&owner, jlu.id(), jlu.id(), U128(1_000)).await?;
+
+    let balance = view::ft_balance_of(jlu.as_account(), jlu.id()).await?;
+    assert_eq!(balance, U128(1_000));
+
+    call::return_self_held_tokens(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    let balance = view::ft_balance_of(&owner, jlu.id()).await?;
+    assert_eq!(balance, U128(1_000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_transfer() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+    call::storage_deposit(jlu.id(), &bob, None, None).await?;
+
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    let (_, events) = call::ft_batch_transfer(
+        &owner,
+        jlu.id(),
+        vec![(alice.id(), U128(100)), (bob.id(), U128(200))],
+    )
+    .await?;
+
+    let transfers: Vec<_> = events
+        .into_iter()
+        .filter_map(|event| match event {
+            ContractEvent::Nep141(Nep141Event {
+                event_kind: Nep141EventKind::FtTransfer(transfers),
+                ..
+            }) => Some(transfers),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].len(), 2);
+
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(700));
+    assert_eq!(view::ft_balance_of(&alice, jlu.id()).await?, U128(100));
+    assert_eq!(view::ft_balance_of(&bob, jlu.id()).await?, U128(200));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_storage_deposit_batch() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+
+    let registered = call::storage_deposit_batch(
+        &owner,
+        jlu.id(),
+        vec![alice.id(), bob.id()],
+        NearToken::from_near(1),
+    )
+    .await?;
+    assert_eq!(registered, vec![bob.id().clone()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sponsored_registration() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    let carol = worker.dev_create_account().await?;
+
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    assert!(call::ft_transfer(&owner, jlu.id(), alice.id(), U128(100))
+        .await
+        .is_err());
+
+    call::fund_sponsorship(&owner, jlu.id(), NearToken::from_near(1)).await?;
+    call::set_sponsorship(&owner, jlu.id(), true, 2).await?;
+
+    call::ft_transfer(&owner, jlu.id(), alice.id(), U128(100)).await?;
+    assert_eq!(view::ft_balance_of(&alice, jlu.id()).await?, U128(100));
+
+    // Only transfers from distributors are sponsored.
+    assert!(call::ft_transfer(&alice, jlu.id(), bob.id(), U128(1))
+        .await
+        .is_err());
+    call::grant_role(&owner, jlu.id(), "distributor", alice.id()).await?;
+    call::ft_transfer(&alice, jlu.id(), bob.id(), U128(1)).await?;
+    assert_eq!(view::ft_balance_of(&bob, jlu.id()).await?, U128(1));
+
+    assert!(call::ft_transfer(&owner, jlu.id(), carol.id(), U128(100))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_allowance_transfer_from() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &bob, None, None).await?;
+
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+    assert!(owner
+        .call(jlu.id(), "ft_approve")
+        .args_json((alice.id(), U128(300)))
+        .max_gas()
+        .transact()
+        .await?
+        .is_failure());
+    call::ft_approve(&owner, jlu.id(), alice.id(), U128(300)).await?;
+    assert_eq!(
+        view::ft_allowance(&jlu, owner.id(), alice.id()).await?,
+        U128(300)
+    );
+
+    call::ft_transfer_from(&alice, jlu.id(), owner.id(), bob.id(), U128(200)).await?;
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(800));
+    assert_eq!(view::ft_balance_of(&bob, jlu.id()).await?, U128(200));
+    assert_eq!(
+        view::ft_allowance(&jlu, owner.id(), alice.id()).await?,
+        U128(100)
+    );
+
+    assert!(
+        call::ft_transfer_from(&alice, jlu.id(), owner.id(), bob.id(), U128(200))
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permit() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let relayer = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    let secret_key = near_crypto::SecretKey::from_random(near_crypto::KeyType::ED25519);
+    call::set_signing_key(&owner, jlu.id(), &secret_key.public_key()).await?;
+
+    let permit = token::Permit {
+        owner_id: owner.id().clone(),
+        spender_id: alice.id().clone(),
+        amount: U128(300),
+        nonce: view::nonce_of(&jlu, owner.id()).await?,
+        deadline: U64(u64::MAX),
+    };
+    let signature = sign_message(
+        &secret_key,
+        jlu.id(),
+        &token::SignedMessage::Permit(permit.clone()),
+    );
+    call::ft_permit(&relayer, jlu.id(), &permit, signature.clone()).await?;
+    assert_eq!(view::nonce_of(&jlu, owner.id()).await?, U64(1));
+    assert_eq!(
+        view::ft_allowance(&jlu, owner.id(), alice.id()).await?,
+        U128(300)
+    );
+
+    assert!(call::ft_permit(&relayer, jlu.id(), &permit, signature)
+        .await
+        .is_err());
+
+    // Named accounts can't sign without setting a signing key first.
+    let permit = token::Permit {
+        owner_id: alice.id().clone(),
+        spender_id: relayer.id().clone(),
+        amount: U128(300),
+        nonce: U64(0),
+        deadline: U64(u64::MAX),
+    };
+    let signature = sign_message(
+        &secret_key,
+        jlu.id(),
+        &token::SignedMessage::Permit(permit.clone()),
+    );
+    assert!(call::ft_permit(&relayer, jlu.id(), &permit, signature)
+        .await
+        .is_err());
+
+    // Implicit accounts sign with the key their ID is derived from without any transaction.
+    let implicit_key = near_crypto::SecretKey::from_random(near_crypto::KeyType::ED25519);
+    let implicit_id = implicit_account_id(&implicit_key.public_key());
+    let permit = token::Permit {
+        owner_id: implicit_id.clone(),
+        spender_id: alice.id().clone(),
+        amount: U128(200),
+        nonce: U64(0),
+        deadline: U64(u64::MAX),
+    };
+    let signature = sign_message(
+        &implicit_key,
+        jlu.id(),
+        &token::SignedMessage::Permit(permit.clone()),
+    );
+    call::ft_permit(&relayer, jlu.id(), &permit, signature).await?;
+    assert_eq!(
+        view::ft_allowance(&jlu, &implicit_id, alice.id()).await?,
+        U128(200)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_signed_transfer() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let relayer = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+    call::storage_deposit(jlu.id(), &relayer, None, None).await?;
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    let secret_key = near_crypto::SecretKey::from_random(near_crypto::KeyType::ED25519);
+    call::set_signing_key(&owner, jlu.id(), &secret_key.public_key()).await?;
+
+    let intent = token::TransferIntent {
+        sender_id: owner.id().clone(),
+        receiver_id: alice.id().clone(),
+        amount: U128(100),
+        fee: U128(5),
+        nonce: U64(0),
+        expires_at: U64(u64::MAX),
+    };
+    assert_eq!(
+        view::transfer_intent_status(&jlu, &intent).await?,
+        token::TransferIntentStatus::Executable
+    );
+    let signature = sign_message(
+        &secret_key,
+        jlu.id(),
+        &token::SignedMessage::TransferIntent(intent.clone()),
+    );
+    call::ft_transfer_signed(&relayer, jlu.id(), &intent, signature.clone()).await?;
+
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(895));
+    assert_eq!(view::ft_balance_of(&alice, jlu.id()).await?, U128(100));
+    assert_eq!(view::ft_balance_of(&relayer, jlu.id()).await?, U128(5));
+    assert_eq!(
+        view::transfer_intent_status(&jlu, &intent).await?,
+        token::TransferIntentStatus::NonceUsed
+    );
+    assert!(
+        call::ft_transfer_signed(&relayer, jlu.id(), &intent, signature)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vesting() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+
+    call::create_vesting(
+        &owner,
+        jlu.id(),
+        &token::VestingParams {
+            beneficiary_id: alice.id().clone(),
+            total: U128(1_000),
+            start: Some(U64(0)),
+            cliff: U64(0),
+            duration: U64(1),
+            revocable: false,
+        },
+        true,
+    )
+    .await?;
+    let schedule_id = call::create_vesting(
+        &owner,
+        jlu.id(),
+        &token::VestingParams {
+            beneficiary_id: alice.id().clone(),
+            total: U128(500),
+            start: None,
+            cliff: U64(0),
+            duration: U64(u64::MAX / 2),
+            revocable: true,
+        },
+        true,
+    )
+    .await?;
+    assert_eq!(view::ft_total_supply(&jlu).await?, U128(1_500));
+
+    assert_eq!(view::vesting_schedules_of(&jlu, alice.id()).await?.len(), 2);
+
+    assert_eq!(call::claim_vested(&alice, jlu.id()).await?, U128(1_000));
+    assert_eq!(view::ft_balance_of(&alice, jlu.id()).await?, U128(1_000));
+    assert!(call::claim_vested(&alice, jlu.id()).await.is_err());
+    let schedules = view::vesting_schedules_of(&jlu, alice.id()).await?;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].0, schedule_id);
+
+    assert_eq!(
+        call::revoke_vesting(&owner, jlu.id(), schedule_id).await?,
+        U128(500)
+    );
+    assert!(call::revoke_vesting(&owner, jlu.id(), schedule_id)
+        .await
+        .is_err());
+    assert!(view::vesting_schedules_of(&jlu, alice.id())
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_locked_balance() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+
+    call::lock(&owner, jlu.id(), U128(600), U64(u64::MAX)).await?;
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(1_000));
+    assert_eq!(
+        view::ft_locked_balance_of(&jlu, owner.id()).await?,
+        U128(600)
+    );
+    assert_eq!(
+        view::ft_unlocked_balance_of(&jlu, owner.id()).await?,
+        U128(400)
+    );
+
+    assert!(call::ft_transfer(&owner, jlu.id(), alice.id(), U128(500))
+        .await
+        .is_err());
+    call::ft_transfer(&owner, jlu.id(), alice.id(), U128(400)).await?;
+    assert!(call::ft_burn(&owner, jlu.id(), U128(1)).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staking() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let unit = token::MIN_STAKE;
+    let alice = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000 * unit)).await?;
+    call::mint(&owner, jlu.id(), alice.id(), U128(1_000 * unit)).await?;
+
+    // Stakes below the minimum are refunded.
+    call::ft_transfer_call(&alice, jlu.id(), jlu.id(), U128(unit - 1), "stake").await?;
+    assert_eq!(
+        view::ft_balance_of(&alice, jlu.id()).await?,
+        U128(1_000 * unit)
+    );
+
+    call::ft_transfer_call(&alice, jlu.id(), jlu.id(), U128(400 * unit), "stake").await?;
+    assert_eq!(
+        view::ft_balance_of(&alice, jlu.id()).await?,
+        U128(600 * unit)
+    );
+
+    call::fund_staking_rewards(&owner, jlu.id(), U128(100), U64(1)).await?;
+    worker.fast_forward(5).await?;
+
+    let rewards = call::claim_rewards(&alice, jlu.id()).await?;
+    assert!(rewards.0 > 0 && rewards.0 <= 100);
+
+    assert!(call::unstake(&alice, jlu.id(), U128(400 * unit - 1))
+        .await
+        .is_err());
+    call::unstake(&alice, jlu.id(), U128(400 * unit)).await?;
+    assert_eq!(
+        view::ft_balance_of(&alice, jlu.id()).await?,
+        U128(1_000 * unit + rewards.0)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_balance_checkpoints() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &alice, None, None).await?;
+
+    let before_mint = worker.view_block().await?.height();
+    call::mint(&owner, jlu.id(), owner.id(), U128(1_000)).await?;
+    let after_mint = worker.view_block().await?.height();
+    call::ft_transfer(&owner, jlu.id(), alice.id(), U128(400)).await?;
+
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, owner.id(), before_mint).await?,
+        U128(0)
+    );
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, owner.id(), after_mint).await?,
+        U128(1_000)
+    );
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, alice.id(), after_mint).await?,
+        U128(0)
+    );
+    assert_eq!(view::ft_total_supply_at(&jlu, before_mint).await?, U128(0));
+    assert_eq!(
+        view::ft_total_supply_at(&jlu, after_mint).await?,
+        U128(1_000)
+    );
+    assert_eq!(view::ft_balance_of(&owner, jlu.id()).await?, U128(600));
+
+    // Only the last checkpoints of an account are kept.
+    for _ in 0..token::MAX_ACCOUNT_CHECKPOINTS {
+        call::ft_transfer(&owner, jlu.id(), alice.id(), U128(1)).await?;
+    }
+    assert!(view::ft_balance_of_at(&jlu, owner.id(), after_mint)
+        .await
+        .is_err());
+    let recent = worker.view_block().await?.height();
+    call::ft_transfer(&owner, jlu.id(), alice.id(), U128(1)).await?;
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, owner.id(), recent).await?,
+        U128(600 - token::MAX_ACCOUNT_CHECKPOINTS as u128)
+    );
+
+    // The history of an account is removed when it unregisters.
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, alice.id(), recent).await?,
+        U128(400 + token::MAX_ACCOUNT_CHECKPOINTS as u128)
+    );
+    assert!(call::storage_unregister(&alice, jlu.id(), true).await?);
+    assert_eq!(
+        view::ft_balance_of_at(&jlu, alice.id(), recent).await?,
+        U128(0)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_governance() -> anyhow::Result<()> {
+    let Init {
+        worker, owner, jlu, ..
+    } = initialize_contracts().await?;
+
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+    call::storage_deposit(jlu.id(), &bob, None, None).await?;
+    call::storage_deposit(jlu.id(), &owner, Some(jlu.id()), None).await?;
+    call::mint(&owner, jlu.id(), alice.id(), U128(1_000)).await?;
+    call::mint(&owner, jlu.id(), jlu.id(), U128(100)).await?;
+    let mut config = token::GovernanceConfig {
+        voting_period: U64(10_000_000_000),
+        execution_delay: U64(10_000_000_000),
+        quorum_bps: 5_000,
+        threshold_bps: 5_000,
+        proposal_threshold_bps: 1_000,
+    };
+    call::set_governance_config(&owner, jlu.id(), &config).await?;
+
+    // Proposals can't call privileged methods of this contract like minting.
+    let mint = token::GovernanceAction::SelfCall {
+        method_name: "mint".to_string(),
+        args: json!({ "account_id": alice.id(), "amount": U128(100) })
+            .to_string()
+            .into_bytes()
+            .into(),
+    };
+    assert!(
+        call::create_proposal(&alice, jlu.id(), "Mint to alice", Some(mint))
+            .await
+            .is_err()
+    );
+
+    let action = token::GovernanceAction::SelfCall {
+        method_name: "return_self_held_tokens".to_string(),
+        args: json!({ "receiver_id": bob.id(), "amount": U128(100) })
+            .to_string()
+            .into_bytes()
+            .into(),
+    };
+    assert!(
+        call::create_proposal(&bob, jlu.id(), "Pay bob", Some(action.clone()))
+            .await
+            .is_err()
+    );
+    let proposal_id = call::create_proposal(&alice, jlu.id(), "Pay bob", Some(action)).await?;
+    let transfer = token::GovernanceAction::Transfer {
+        receiver_id: bob.id().clone(),
+        amount: NearToken::from_near(1),
+    };
+    let transfer_id =
+        call::create_proposal(&alice, jlu.id(), "Pay bob NEAR", Some(transfer)).await?;
+    call::fund_governance_treasury(&owner, jlu.id(), NearToken::from_near(1)).await?;
+
+    call::vote(&alice, jlu.id(), proposal_id, token::Vote::Yes).await?;
+    call::vote(&alice, jlu.id(), transfer_id, token::Vote::Yes).await?;
+    assert!(call::vote(&bob, jlu.id(), proposal_id, token::Vote::No)
+        .await
+        .is_err());
+    assert!(call::execute_proposal(&bob, jlu.id(), proposal_id)
+        .await
+        .is_err());
+
+    worker.fast_forward(100).await?;
+    assert_eq!(
+        view::proposal_status(&jlu, proposal_id).await?,
+        token::ProposalStatus::Passed
+    );
+    call::execute_proposal(&bob, jlu.id(), proposal_id).await?;
+    assert_eq!(view::ft_balance_of(&bob, jlu.id()).await?, U128(100));
+    assert_eq!(
+        view::proposal_status(&jlu, proposal_id).await?,
+        token::ProposalStatus::Executed
+    );
+    let bob_balance = bob.view_account().await?.balance;
+    call::execute_proposal(&owner, jlu.id(), transfer_id).await?;
+    assert_eq!(
+        bob.view_account().await?.balance,
+        bob_balance.saturating_add(NearToken::from_near(1))
+    );
+
+    // Passed proposals wait for the execution delay, during which the owner can veto them.
+    config.execution_delay = U64(24 * 60 * 60 * 1_000_000_000);
+    call::set_governance_config(&owner, jlu.id(), &config).await?;
+    let proposal_id = call::create_proposal(&alice, jlu.id(), "Signal", None).await?;
+    call::vote(&alice, jlu.id(), proposal_id, token::Vote::Yes).await?;
+    worker.fast_forward(100).await?;
+    assert_eq!(
+        view::proposal_status(&jlu, proposal_id).await?,
+        token::ProposalStatus::Passed
+    );
+    assert!(call::execute_proposal(&bob, jlu.id(), proposal_id)
+        .await
+        .is_err());
+    assert!(call::veto_proposal(&alice, jlu.id(), proposal_id)
+        .await
+        .is_err());
+    call::veto_proposal(&owner, jlu.id(), proposal_id).await?;
+    assert_eq!(
+        view::proposal_status(&jlu, proposal_id).await?,
+        token::ProposalStatus::Vetoed
+    );
+
+    // Dust transfers that prune the checkpoints of a holder don't keep it from voting.
+    config.voting_period = U64(60 * 60 * 1_000_000_000);
+    call::set_governance_config(&owner, jlu.id(), &config).await?;
+    let proposal_id = call::create_proposal(&alice, jlu.id(), "Dust", None).await?;
+    for _ in 0..=token::MAX_ACCOUNT_CHECKPOINTS {
+        call::ft_transfer(&bob, jlu.id(), alice.id(), U128(1)).await?;
+    }
+    let snapshot_block = view::proposal(&jlu, proposal_id)
+        .await?
+        .unwrap()
+        .snapshot_block;
+    assert!(view::ft_balance_of_at(&jlu, alice.id(), snapshot_block.0)
+        .await
+        .is_err());
+    call::vote(&alice, jlu.id(), proposal_id, token::Vote::Yes).await?;
+    assert_eq!(
+        view::proposal(&jlu, proposal_id).await?.unwrap().yes,
+        U128(1_000)
+    );
+
     Ok(())
 }
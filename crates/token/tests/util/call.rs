@@ -1,5 +1,10 @@
 use super::{log_tx_result, ContractEvent};
-use near_sdk::{json_types::U128, serde::Serialize, serde_json::json, AccountId, NearToken};
+use near_sdk::{
+    json_types::{Base58CryptoHash, Base64VecU8, U128, U64},
+    serde::Serialize,
+    serde_json::json,
+    AccountId, NearToken,
+};
 use near_workspaces::{
     result::{ExecutionResult, Value},
     Account, Contract,
@@ -24,6 +29,24 @@ pub async fn storage_deposit(
     Ok(res)
 }
 
+pub async fn storage_unregister(
+    sender: &Account,
+    token_id: &AccountId,
+    force: bool,
+) -> anyhow::Result<bool> {
+    let (res, _) = log_tx_result(
+        &format!("{} storage_unregister", token_id),
+        sender
+            .call(token_id, "storage_unregister")
+            .args_json((Some(force),))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
 pub async fn mint_tokens(
     token: &Contract,
     receiver: &AccountId,
@@ -40,6 +63,24 @@ pub async fn mint_tokens(
     Ok(res)
 }
 
+pub async fn ft_transfer(
+    sender: &Account,
+    token_id: &AccountId,
+    receiver_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_transfer", token_id),
+        sender
+            .call(token_id, "ft_transfer")
+            .args_json((receiver_id, amount, Option::<String>::None))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
 pub async fn ft_transfer_call<T: Serialize>(
     sender: &Account,
     token_id: &AccountId,
@@ -63,3 +104,770 @@ pub async fn ft_transfer_call<T: Serialize>(
             .await?,
     )
 }
+
+pub async fn close_migration(
+    sender: &Account,
+    token_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} close_migration", token_id),
+        sender
+            .call(token_id, "close_migration")
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn set_migration_ratio(
+    sender: &Account,
+    token_id: &AccountId,
+    source_id: &AccountId,
+    numerator: U128,
+    denominator: U128,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_migration_ratio", token_id),
+        sender
+            .call(token_id, "set_migration_ratio")
+            .args_json((source_id, numerator, denominator))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn redeem(
+    sender: &Account,
+    token_id: &AccountId,
+    source_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} redeem", token_id),
+        sender
+            .call(token_id, "redeem")
+            .args_json((source_id, amount))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn mint(
+    sender: &Account,
+    token_id: &AccountId,
+    account_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} mint", token_id),
+        sender
+            .call(token_id, "mint")
+            .args_json((account_id, amount, None::<String>))
+            .max_gas()
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn set_max_supply(
+    sender: &Account,
+    token_id: &AccountId,
+    max_supply: Option<U128>,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_max_supply", token_id),
+        sender
+            .call(token_id, "set_max_supply")
+            .args_json((max_supply,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn ft_burn(
+    sender: &Account,
+    token_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_burn", token_id),
+        sender
+            .call(token_id, "ft_burn")
+            .args_json((amount, None::<String>))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+/// Upgrades a contract of version 1, which deploys the code given as raw input and migrates.
+pub async fn upgrade_v1(
+    sender: &Account,
+    token_id: &AccountId,
+    code: Vec<u8>,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} upgrade", token_id),
+        sender
+            .call(token_id, "upgrade")
+            .args(code)
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn register_deployed_code(
+    sender: &Account,
+    token_id: &AccountId,
+    code: Vec<u8>,
+) -> anyhow::Result<Base58CryptoHash> {
+    let (res, _) = log_tx_result(
+        &format!("{} register_deployed_code", token_id),
+        sender
+            .call(token_id, "register_deployed_code")
+            .args(code)
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn stage_code(
+    sender: &Account,
+    token_id: &AccountId,
+    code: Vec<u8>,
+) -> anyhow::Result<Base58CryptoHash> {
+    let (res, _) = log_tx_result(
+        &format!("{} stage_code", token_id),
+        sender
+            .call(token_id, "stage_code")
+            .args(code)
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn cancel_staged_code(
+    sender: &Account,
+    token_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} cancel_staged_code", token_id),
+        sender
+            .call(token_id, "cancel_staged_code")
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+/// Returns whether migrating with the deployed code succeeded.
+pub async fn deploy_staged(sender: &Account, token_id: &AccountId) -> anyhow::Result<bool> {
+    let (res, _) = log_tx_result(
+        &format!("{} deploy_staged", token_id),
+        sender
+            .call(token_id, "deploy_staged")
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn set_upgrade_timelock(
+    sender: &Account,
+    token_id: &AccountId,
+    duration: U64,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_upgrade_timelock", token_id),
+        sender
+            .call(token_id, "set_upgrade_timelock")
+            .args_json((duration,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn propose_owner(
+    sender: &Account,
+    token_id: &AccountId,
+    account_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} propose_owner", token_id),
+        sender
+            .call(token_id, "propose_owner")
+            .args_json((account_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn accept_owner(
+    sender: &Account,
+    token_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} accept_owner", token_id),
+        sender
+            .call(token_id, "accept_owner")
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn grant_role(
+    sender: &Account,
+    token_id: &AccountId,
+    role: &str,
+    account_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} grant_role", token_id),
+        sender
+            .call(token_id, "grant_role")
+            .args_json((role, account_id))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn revoke_role(
+    sender: &Account,
+    token_id: &AccountId,
+    role: &str,
+    account_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} revoke_role", token_id),
+        sender
+            .call(token_id, "revoke_role")
+            .args_json((role, account_id))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn pause<T: Serialize>(
+    sender: &Account,
+    token_id: &AccountId,
+    feature: T,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} pause", token_id),
+        sender
+            .call(token_id, "pause")
+            .args_json((feature,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn set_multisig(
+    sender: &Account,
+    token_id: &AccountId,
+    members: Vec<&AccountId>,
+    threshold: u32,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_multisig", token_id),
+        sender
+            .call(token_id, "set_multisig")
+            .args_json((members, threshold))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn multisig_propose<T: Serialize>(
+    sender: &Account,
+    token_id: &AccountId,
+    action: T,
+) -> anyhow::Result<U64> {
+    let (res, _) = log_tx_result(
+        &format!("{} multisig_propose", token_id),
+        sender
+            .call(token_id, "multisig_propose")
+            .args_json((action,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn multisig_confirm(
+    sender: &Account,
+    token_id: &AccountId,
+    proposal_id: U64,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} multisig_confirm", token_id),
+        sender
+            .call(token_id, "multisig_confirm")
+            .args_json((proposal_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn multisig_execute(
+    sender: &Account,
+    token_id: &AccountId,
+    proposal_id: U64,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} multisig_execute", token_id),
+        sender
+            .call(token_id, "multisig_execute")
+            .args_json((proposal_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn block_account(
+    sender: &Account,
+    token_id: &AccountId,
+    account_id: &AccountId,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} block_account", token_id),
+        sender
+            .call(token_id, "block_account")
+            .args_json((account_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn return_self_held_tokens(
+    sender: &Account,
+    token_id: &AccountId,
+    receiver_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} return_self_held_tokens", token_id),
+        sender
+            .call(token_id, "return_self_held_tokens")
+            .args_json((receiver_id, amount))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn ft_batch_transfer(
+    sender: &Account,
+    token_id: &AccountId,
+    transfers: Vec<(&AccountId, U128)>,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_batch_transfer", token_id),
+        sender
+            .call(token_id, "ft_batch_transfer")
+            .args_json((transfers, Option::<String>::None))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn storage_deposit_batch(
+    sender: &Account,
+    token_id: &AccountId,
+    account_ids: Vec<&AccountId>,
+    deposit: NearToken,
+) -> anyhow::Result<Vec<AccountId>> {
+    let (res, _) = log_tx_result(
+        &format!("{} storage_deposit_batch", token_id),
+        sender
+            .call(token_id, "storage_deposit_batch")
+            .args_json((account_ids,))
+            .max_gas()
+            .deposit(deposit)
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn fund_sponsorship(
+    sender: &Account,
+    token_id: &AccountId,
+    deposit: NearToken,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} fund_sponsorship", token_id),
+        sender
+            .call(token_id, "fund_sponsorship")
+            .max_gas()
+            .deposit(deposit)
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn set_sponsorship(
+    sender: &Account,
+    token_id: &AccountId,
+    enabled: bool,
+    daily_limit: u32,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_sponsorship", token_id),
+        sender
+            .call(token_id, "set_sponsorship")
+            .args_json((enabled, daily_limit))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn ft_approve(
+    sender: &Account,
+    token_id: &AccountId,
+    spender_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_approve", token_id),
+        sender
+            .call(token_id, "ft_approve")
+            .args_json((spender_id, amount))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn ft_transfer_from(
+    sender: &Account,
+    token_id: &AccountId,
+    owner_id: &AccountId,
+    receiver_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_transfer_from", token_id),
+        sender
+            .call(token_id, "ft_transfer_from")
+            .args_json((owner_id, receiver_id, amount, Option::<String>::None))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn set_signing_key(
+    sender: &Account,
+    token_id: &AccountId,
+    public_key: &near_crypto::PublicKey,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_signing_key", token_id),
+        sender
+            .call(token_id, "set_signing_key")
+            .args_json((public_key.to_string(),))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn ft_permit(
+    sender: &Account,
+    token_id: &AccountId,
+    permit: &token::Permit,
+    signature: Base64VecU8,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_permit", token_id),
+        sender
+            .call(token_id, "ft_permit")
+            .args_json((permit, signature))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn ft_transfer_signed(
+    sender: &Account,
+    token_id: &AccountId,
+    intent: &token::TransferIntent,
+    signature: Base64VecU8,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} ft_transfer_signed", token_id),
+        sender
+            .call(token_id, "ft_transfer_signed")
+            .args_json((intent, signature))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn create_vesting(
+    sender: &Account,
+    token_id: &AccountId,
+    params: &token::VestingParams,
+    mint: bool,
+) -> anyhow::Result<U64> {
+    let (res, _) = log_tx_result(
+        &format!("{} create_vesting", token_id),
+        sender
+            .call(token_id, "create_vesting")
+            .args_json((params, mint))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn revoke_vesting(
+    sender: &Account,
+    token_id: &AccountId,
+    schedule_id: U64,
+) -> anyhow::Result<U128> {
+    let (res, _) = log_tx_result(
+        &format!("{} revoke_vesting", token_id),
+        sender
+            .call(token_id, "revoke_vesting")
+            .args_json((schedule_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn claim_vested(sender: &Account, token_id: &AccountId) -> anyhow::Result<U128> {
+    let (res, _) = log_tx_result(
+        &format!("{} claim_vested", token_id),
+        sender
+            .call(token_id, "claim_vested")
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn lock(
+    sender: &Account,
+    token_id: &AccountId,
+    amount: U128,
+    until: U64,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} lock", token_id),
+        sender
+            .call(token_id, "lock")
+            .args_json((amount, until))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn fund_staking_rewards(
+    sender: &Account,
+    token_id: &AccountId,
+    amount: U128,
+    duration: U64,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} fund_staking_rewards", token_id),
+        sender
+            .call(token_id, "fund_staking_rewards")
+            .args_json((amount, duration))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn unstake(
+    sender: &Account,
+    token_id: &AccountId,
+    amount: U128,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} unstake", token_id),
+        sender
+            .call(token_id, "unstake")
+            .args_json((amount,))
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn claim_rewards(sender: &Account, token_id: &AccountId) -> anyhow::Result<U128> {
+    let (res, _) = log_tx_result(
+        &format!("{} claim_rewards", token_id),
+        sender
+            .call(token_id, "claim_rewards")
+            .max_gas()
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn set_governance_config(
+    sender: &Account,
+    token_id: &AccountId,
+    config: &token::GovernanceConfig,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} set_governance_config", token_id),
+        sender
+            .call(token_id, "set_governance_config")
+            .args_json((config,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn fund_governance_treasury(
+    sender: &Account,
+    token_id: &AccountId,
+    deposit: NearToken,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} fund_governance_treasury", token_id),
+        sender
+            .call(token_id, "fund_governance_treasury")
+            .deposit(deposit)
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
+
+pub async fn create_proposal(
+    sender: &Account,
+    token_id: &AccountId,
+    description: &str,
+    action: Option<token::GovernanceAction>,
+) -> anyhow::Result<u32> {
+    let (res, _) = log_tx_result(
+        &format!("{} create_proposal", token_id),
+        sender
+            .call(token_id, "create_proposal")
+            .args_json((description, action))
+            .max_gas()
+            .deposit(NearToken::from_millinear(100))
+            .transact()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn vote(
+    sender: &Account,
+    token_id: &AccountId,
+    proposal_id: u32,
+    vote: token::Vote,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} vote", token_id),
+        sender
+            .call(token_id, "vote")
+            .args_json((proposal_id, vote))
+            .max_gas()
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn execute_proposal(
+    sender: &Account,
+    token_id: &AccountId,
+    proposal_id: u32,
+) -> anyhow::Result<(ExecutionResult<Value>, Vec<ContractEvent>)> {
+    log_tx_result(
+        &format!("{} execute_proposal", token_id),
+        sender
+            .call(token_id, "execute_proposal")
+            .args_json((proposal_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )
+}
+
+pub async fn veto_proposal(
+    sender: &Account,
+    token_id: &AccountId,
+    proposal_id: u32,
+) -> anyhow::Result<ExecutionResult<Value>> {
+    let (res, _) = log_tx_result(
+        &format!("{} veto_proposal", token_id),
+        sender
+            .call(token_id, "veto_proposal")
+            .args_json((proposal_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+    Ok(res)
+}
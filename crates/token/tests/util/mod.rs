@@ -6,6 +6,8 @@ pub use event::*;
 
 use crate::*;
 use near_sdk::{
+    borsh,
+    json_types::Base64VecU8,
     serde::Serialize,
     serde_json::{self, json},
 };
@@ -13,10 +15,10 @@ use near_workspaces::{
     network::Sandbox,
     result::{ExecutionFinalResult, ExecutionResult, Value, ViewResultDetails},
     types::NearToken,
-    Account, Contract, Worker,
+    Account, AccountId, Contract, Worker,
 };
 use owo_colors::OwoColorize;
-use std::fmt;
+use std::fmt::{self, Write};
 use tokio::fs;
 
 #[macro_export]
@@ -110,6 +112,14 @@ pub async fn initialize_token(
     Ok(token_contract)
 }
 
+/// Fast forwards until the latest block is at least at `timestamp` in nanoseconds.
+pub async fn wait_until(worker: &Worker<Sandbox>, timestamp: u64) -> anyhow::Result<()> {
+    while worker.view_block().await?.timestamp() < timestamp {
+        worker.fast_forward(1_000).await?;
+    }
+    Ok(())
+}
+
 pub fn assert_ft_mint_events<T>(actual: &T, events: Vec<FtMint>) -> anyhow::Result<()>
 where
     T: Serialize + fmt::Debug + Clone,
@@ -190,3 +200,24 @@ pub fn log_view_result(res: ViewResultDetails) -> anyhow::Result<ViewResultDetai
     }
     Ok(res)
 }
+
+/// Account ID of the implicit account that `public_key` controls.
+pub fn implicit_account_id(public_key: &near_crypto::PublicKey) -> AccountId {
+    let mut hex = String::new();
+    for byte in public_key.key_data() {
+        write!(hex, "{:02x}", byte).unwrap();
+    }
+    hex.parse().unwrap()
+}
+
+pub fn sign_message(
+    secret_key: &near_crypto::SecretKey,
+    token_id: &near_sdk::AccountId,
+    message: &token::SignedMessage,
+) -> Base64VecU8 {
+    let message = borsh::to_vec(&(token_id, message)).unwrap();
+    match secret_key.sign(&message) {
+        near_crypto::Signature::ED25519(signature) => signature.to_bytes().to_vec().into(),
+        _ => unreachable!(),
+    }
+}
@@ -1,6 +1,6 @@
 use super::log_view_result;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
-use near_sdk::json_types::U128;
+use near_sdk::json_types::{Base58CryptoHash, U128, U64};
 use near_workspaces::{network::Sandbox, Account, AccountId, Contract, Worker};
 
 pub async fn ft_balance_of(sender: &Account, token_id: &AccountId) -> anyhow::Result<U128> {
@@ -27,3 +27,224 @@ pub async fn ft_metadata(
     let res = log_view_result(worker.view(token_id, "ft_metadata").await?)?;
     Ok(res.json()?)
 }
+
+pub async fn migrated_amount_of(sender: &Account, token_id: &AccountId) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        sender
+            .call(token_id, "migrated_amount_of")
+            .args_json((sender.id(),))
+            .max_gas()
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn migration_stats(contract: &Contract) -> anyhow::Result<token::MigrationStats> {
+    let res = log_view_result(contract.call("migration_stats").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn contract_version(contract: &Contract) -> anyhow::Result<u32> {
+    let res = log_view_result(contract.call("contract_version").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn staged_code_hash(contract: &Contract) -> anyhow::Result<Option<Base58CryptoHash>> {
+    let res = log_view_result(contract.call("staged_code_hash").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn staged_code_eligible_at(contract: &Contract) -> anyhow::Result<Option<U64>> {
+    let res = log_view_result(
+        contract
+            .call("staged_code_eligible_at")
+            .max_gas()
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn deployed_code_hash(contract: &Contract) -> anyhow::Result<Option<Base58CryptoHash>> {
+    let res = log_view_result(contract.call("deployed_code_hash").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn upgrade_timelock(contract: &Contract) -> anyhow::Result<U64> {
+    let res = log_view_result(contract.call("upgrade_timelock").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn max_supply(contract: &Contract) -> anyhow::Result<Option<U128>> {
+    let res = log_view_result(contract.call("max_supply").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn vesting_schedules_of(
+    contract: &Contract,
+    account_id: &AccountId,
+) -> anyhow::Result<Vec<(U64, token::VestingSchedule)>> {
+    let res = log_view_result(
+        contract
+            .call("vesting_schedules_of")
+            .args_json((account_id,))
+            .max_gas()
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn owner(contract: &Contract) -> anyhow::Result<AccountId> {
+    let res = log_view_result(contract.call("owner").max_gas().view().await?)?;
+    Ok(res.json()?)
+}
+
+pub async fn role_members(contract: &Contract, role: &str) -> anyhow::Result<Vec<AccountId>> {
+    let res = log_view_result(
+        contract
+            .call("role_members")
+            .args_json((role,))
+            .max_gas()
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn ft_allowance(
+    contract: &Contract,
+    owner_id: &AccountId,
+    spender_id: &AccountId,
+) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        contract
+            .call("ft_allowance")
+            .args_json((owner_id, spender_id))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn nonce_of(contract: &Contract, account_id: &AccountId) -> anyhow::Result<U64> {
+    let res = log_view_result(
+        contract
+            .call("nonce_of")
+            .args_json((account_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn transfer_intent_status(
+    contract: &Contract,
+    intent: &token::TransferIntent,
+) -> anyhow::Result<token::TransferIntentStatus> {
+    let res = log_view_result(
+        contract
+            .call("transfer_intent_status")
+            .args_json((intent,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn ft_locked_balance_of(
+    contract: &Contract,
+    account_id: &AccountId,
+) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        contract
+            .call("ft_locked_balance_of")
+            .args_json((account_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn ft_unlocked_balance_of(
+    contract: &Contract,
+    account_id: &AccountId,
+) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        contract
+            .call("ft_unlocked_balance_of")
+            .args_json((account_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn ft_balance_of_at(
+    contract: &Contract,
+    account_id: &AccountId,
+    block_height: u64,
+) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        contract
+            .call("ft_balance_of_at")
+            .args_json((account_id, U64(block_height)))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn ft_total_supply_at(contract: &Contract, block_height: u64) -> anyhow::Result<U128> {
+    let res = log_view_result(
+        contract
+            .call("ft_total_supply_at")
+            .args_json((U64(block_height),))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn proposal(
+    contract: &Contract,
+    proposal_id: u32,
+) -> anyhow::Result<Option<token::GovernanceProposal>> {
+    let res = log_view_result(
+        contract
+            .call("proposal")
+            .args_json((proposal_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn proposal_status(
+    contract: &Contract,
+    proposal_id: u32,
+) -> anyhow::Result<token::ProposalStatus> {
+    let res = log_view_result(
+        contract
+            .call("proposal_status")
+            .args_json((proposal_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}
+
+pub async fn multisig_proposal(
+    contract: &Contract,
+    proposal_id: U64,
+) -> anyhow::Result<Option<token::MultisigProposal>> {
+    let res = log_view_result(
+        contract
+            .call("multisig_proposal")
+            .args_json((proposal_id,))
+            .view()
+            .await?,
+    )?;
+    Ok(res.json()?)
+}